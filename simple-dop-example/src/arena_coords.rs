@@ -0,0 +1,98 @@
+use crate::prelude::*;
+
+
+
+// every conversion between screen pixels and arena units goes through here, so that what's drawn and
+// what the mouse points at can't disagree (rendering uses `Camera::get_view()`, aiming uses `get_aim_target()`)
+
+
+
+// everything needed to go between arena and screen coordinates for a single frame
+// (the arena goes from 0 to 1 on both axes, the screen is in pixels from the top left)
+#[derive(Debug, Clone, Copy)]
+pub struct ArenaView {
+	// the arena position shown in the middle of the window
+	pub center: Vec2,
+	pub zoom: f32,
+	pub fit_mode: CameraFitMode,
+	pub screen_size: UVec2,
+}
+
+impl ArenaView {
+	// pixels per arena unit on each axis
+	pub fn get_scale(&self) -> Vec2 {
+		let screen_size = self.screen_size.as_vec2();
+		let scale = match self.fit_mode {
+			CameraFitMode::Fit => Vec2::splat(screen_size.min_element()),
+			CameraFitMode::Fill => Vec2::splat(screen_size.max_element()),
+			CameraFitMode::Stretch => screen_size,
+		};
+		// a minimized window has a size of 0, which would make `screen_to_arena()` divide by 0
+		(scale * self.zoom).max(Vec2::ONE)
+	}
+	pub fn arena_to_screen(&self, arena_pos: Vec2) -> Vec2 {
+		self.screen_size.as_vec2() * 0.5 + (arena_pos - self.center) * self.get_scale()
+	}
+	pub fn screen_to_arena(&self, screen_pos: Vec2) -> Vec2 {
+		(screen_pos - self.screen_size.as_vec2() * 0.5) / self.get_scale() + self.center
+	}
+}
+
+
+
+// the top left and size on screen of a rect in the arena
+pub fn arena_placement_to_screen_placement(arena_pos: Vec2, arena_size: Vec2, view: &ArenaView) -> ((f32, f32), (f32, f32)) {
+	let top_left = view.arena_to_screen(arena_pos);
+	let bottom_right = view.arena_to_screen(arena_pos + arena_size);
+	let size = bottom_right - top_left;
+	((top_left.x, top_left.y), (size.x, size.y))
+}
+
+
+
+// the arena position under the mouse, the shake is left out so that the aim doesn't jitter while the screen shakes
+pub fn get_aim_target(playing_data: &PlayingData, mouse_pos: Vec2, screen_size: UVec2) -> Vec2 {
+	let view = playing_data.camera.get_steady_view(&playing_data.settings, playing_data.get_tick_alpha(), screen_size);
+	view.screen_to_arena(mouse_pos)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn get_test_views() -> Vec<ArenaView> {
+		let mut output = vec!();
+		for screen_size in [UVec2::new(1280, 720), UVec2::new(720, 1280), UVec2::new(800, 800)] {
+			for fit_mode in [CameraFitMode::Fit, CameraFitMode::Fill, CameraFitMode::Stretch] {
+				for (center, zoom) in [(Vec2::splat(0.5), 1.), (Vec2::new(0.3, 0.8), 2.5)] {
+					output.push(ArenaView {center, zoom, fit_mode, screen_size});
+				}
+			}
+		}
+		output
+	}
+	
+	#[test]
+	fn arena_to_screen_round_trips() {
+		for view in get_test_views() {
+			for arena_pos in [Vec2::ZERO, Vec2::ONE, Vec2::new(0.25, 0.9), Vec2::new(-0.5, 1.5)] {
+				let round_trip = view.screen_to_arena(view.arena_to_screen(arena_pos));
+				assert!((round_trip - arena_pos).length() < 1e-5, "{arena_pos} became {round_trip} with {view:?}");
+			}
+		}
+	}
+	
+	#[test]
+	fn screen_to_arena_round_trips() {
+		for view in get_test_views() {
+			let screen_size = view.screen_size.as_vec2();
+			for screen_pos in [Vec2::ZERO, screen_size, screen_size * 0.5, Vec2::new(17., screen_size.y - 3.)] {
+				let round_trip = view.arena_to_screen(view.screen_to_arena(screen_pos));
+				assert!((round_trip - screen_pos).length() < 1e-2, "{screen_pos} became {round_trip} with {view:?}");
+			}
+		}
+	}
+	
+}
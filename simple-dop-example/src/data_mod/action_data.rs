@@ -0,0 +1,289 @@
+use crate::prelude::*;
+use std::{fs, collections::HashMap};
+
+
+
+// everything the player can do with a key or mouse button, the update phases only ever check these
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+	MoveUp,
+	MoveDown,
+	MoveLeft,
+	MoveRight,
+	Fire,
+	Weapon1,
+	Weapon2,
+	Weapon3,
+	Weapon4,
+	Weapon5,
+	Weapon6,
+	Weapon7,
+	Weapon8,
+	Weapon9,
+	Pause,
+	Confirm,
+	Back,
+	MenuLeft,
+	MenuRight,
+	ReplayPause,
+	ReplayFastForward,
+	ReplayStep,
+	Reload,
+}
+
+impl Action {
+	pub const ALL: [Self; 23] = [
+		Self::MoveUp, Self::MoveDown, Self::MoveLeft, Self::MoveRight,
+		Self::Fire,
+		Self::Weapon1, Self::Weapon2, Self::Weapon3, Self::Weapon4, Self::Weapon5, Self::Weapon6, Self::Weapon7, Self::Weapon8, Self::Weapon9,
+		Self::Pause, Self::Confirm, Self::Back, Self::MenuLeft, Self::MenuRight,
+		Self::ReplayPause, Self::ReplayFastForward, Self::ReplayStep,
+		Self::Reload,
+	];
+	// index 0 switches to slot 1
+	pub const WEAPON_SLOTS: [Self; 9] = [Self::Weapon1, Self::Weapon2, Self::Weapon3, Self::Weapon4, Self::Weapon5, Self::Weapon6, Self::Weapon7, Self::Weapon8, Self::Weapon9];
+	// the names used in the bindings file
+	pub fn get_name(&self) -> &'static str {
+		match self {
+			Self::MoveUp => "move up",
+			Self::MoveDown => "move down",
+			Self::MoveLeft => "move left",
+			Self::MoveRight => "move right",
+			Self::Fire => "fire",
+			Self::Weapon1 => "weapon 1",
+			Self::Weapon2 => "weapon 2",
+			Self::Weapon3 => "weapon 3",
+			Self::Weapon4 => "weapon 4",
+			Self::Weapon5 => "weapon 5",
+			Self::Weapon6 => "weapon 6",
+			Self::Weapon7 => "weapon 7",
+			Self::Weapon8 => "weapon 8",
+			Self::Weapon9 => "weapon 9",
+			Self::Pause => "pause",
+			Self::Confirm => "confirm",
+			Self::Back => "back",
+			Self::MenuLeft => "menu left",
+			Self::MenuRight => "menu right",
+			Self::ReplayPause => "replay pause",
+			Self::ReplayFastForward => "replay fast forward",
+			Self::ReplayStep => "replay step",
+			Self::Reload => "reload",
+		}
+	}
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|action| action.get_name() == name)
+	}
+	pub fn get_default_bindings(&self) -> Vec<Binding> {
+		use Binding::*;
+		match self {
+			Self::MoveUp => vec!(Key (KeyCode::W), Key (KeyCode::Up)),
+			Self::MoveDown => vec!(Key (KeyCode::S), Key (KeyCode::Down)),
+			Self::MoveLeft => vec!(Key (KeyCode::A), Key (KeyCode::Left)),
+			Self::MoveRight => vec!(Key (KeyCode::D), Key (KeyCode::Right)),
+			Self::Fire => vec!(Mouse (MouseButton::Left)),
+			Self::Weapon1 => vec!(Key (KeyCode::Key1)),
+			Self::Weapon2 => vec!(Key (KeyCode::Key2)),
+			Self::Weapon3 => vec!(Key (KeyCode::Key3)),
+			Self::Weapon4 => vec!(Key (KeyCode::Key4)),
+			Self::Weapon5 => vec!(Key (KeyCode::Key5)),
+			Self::Weapon6 => vec!(Key (KeyCode::Key6)),
+			Self::Weapon7 => vec!(Key (KeyCode::Key7)),
+			Self::Weapon8 => vec!(Key (KeyCode::Key8)),
+			Self::Weapon9 => vec!(Key (KeyCode::Key9)),
+			Self::Pause => vec!(Key (KeyCode::Escape)),
+			Self::Confirm => vec!(Key (KeyCode::Space), Key (KeyCode::Return)),
+			Self::Back => vec!(Key (KeyCode::Escape)),
+			Self::MenuLeft => vec!(Key (KeyCode::Left)),
+			Self::MenuRight => vec!(Key (KeyCode::Right)),
+			Self::ReplayPause => vec!(Key (KeyCode::Space)),
+			Self::ReplayFastForward => vec!(Key (KeyCode::F)),
+			Self::ReplayStep => vec!(Key (KeyCode::Period)),
+			Self::Reload => vec!(CtrlKey (KeyCode::R)),
+		}
+	}
+	pub fn get_contexts(&self) -> &'static [ActionContext] {
+		use ActionContext::*;
+		match self {
+			Self::MoveUp | Self::MoveDown | Self::MoveLeft | Self::MoveRight | Self::Fire | Self::Pause => &[Playing],
+			Self::Weapon1 | Self::Weapon2 | Self::Weapon3 | Self::Weapon4 | Self::Weapon5 | Self::Weapon6 | Self::Weapon7 | Self::Weapon8 | Self::Weapon9 => &[Playing],
+			Self::Confirm | Self::MenuLeft | Self::MenuRight => &[Menus],
+			Self::Back => &[Menus, Replay],
+			Self::ReplayPause | Self::ReplayFastForward | Self::ReplayStep => &[Replay],
+			Self::Reload => &[Everywhere],
+		}
+	}
+	// two actions can share a binding as long as they're never checked at the same time (like pause and back)
+	pub fn conflicts_with(&self, other: Action) -> bool {
+		if *self == other {return false;}
+		let (contexts, other_contexts) = (self.get_contexts(), other.get_contexts());
+		contexts.contains(&ActionContext::Everywhere)
+			|| other_contexts.contains(&ActionContext::Everywhere)
+			|| contexts.iter().any(|context| other_contexts.contains(context))
+	}
+}
+
+
+
+// where an action is checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionContext {
+	Playing,
+	Menus,
+	Replay,
+	Everywhere,
+}
+
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+	Key (KeyCode),
+	// only counts while ctrl is held
+	CtrlKey (KeyCode),
+	Mouse (MouseButton),
+}
+
+impl Binding {
+	pub fn is_down(&self, input: &InputSnapshot) -> bool {
+		match self {
+			Self::Key (key) => input.key_is_down(*key),
+			Self::CtrlKey (key) => input.ctrl_is_down && input.key_is_down(*key),
+			Self::Mouse (button) => input.mouse_buttons_down.contains(button),
+		}
+	}
+	pub fn was_pressed(&self, input: &InputSnapshot) -> bool {
+		match self {
+			Self::Key (key) => input.key_was_pressed(*key),
+			Self::CtrlKey (key) => input.ctrl_is_down && input.key_was_pressed(*key),
+			Self::Mouse (button) => input.mouse_buttons_pressed.contains(button),
+		}
+	}
+	// the inverse of `parse_binding()`
+	pub fn get_name(&self) -> String {
+		match self {
+			Self::Key (key) => format!("{key:?}"),
+			Self::CtrlKey (key) => format!("Ctrl+{key:?}"),
+			Self::Mouse (button) => format!("Mouse{button:?}"),
+		}
+	}
+}
+
+
+
+// every key that can be bound, the names in the bindings file are the `Debug` names of these (`W`, `Up`, `Key1`, `Space`, etc)
+pub const BINDABLE_KEYS: &[KeyCode] = {
+	use KeyCode::*;
+	&[
+		Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Key0,
+		A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+		Escape, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+		Insert, Home, Delete, End, PageDown, PageUp,
+		Left, Up, Right, Down, Back, Return, Space, Tab,
+		Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+		NumpadAdd, NumpadDivide, NumpadDecimal, NumpadEnter, NumpadMultiply, NumpadSubtract,
+		Apostrophe, Backslash, Comma, Equals, Grave, LBracket, Minus, Period, RBracket, Semicolon, Slash,
+		LAlt, LControl, LShift, RAlt, RControl, RShift,
+	]
+};
+pub const BINDABLE_MOUSE_BUTTONS: &[MouseButton] = &[MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+
+pub fn parse_binding(name: &str) -> Option<Binding> {
+	let parse_key = |name: &str| BINDABLE_KEYS.iter().copied().find(|key| format!("{key:?}") == name);
+	if let Some(key_name) = name.strip_prefix("Ctrl+") {
+		return parse_key(key_name).map(Binding::CtrlKey);
+	}
+	if let Some(button_name) = name.strip_prefix("Mouse") {
+		return BINDABLE_MOUSE_BUTTONS.iter().copied().find(|button| format!("{button:?}") == button_name).map(Binding::Mouse);
+	}
+	parse_key(name).map(Binding::Key)
+}
+
+
+
+#[derive(Debug, Clone)]
+pub struct InputBindings {
+	pub bindings: HashMap<Action, Vec<Binding>>,
+}
+
+impl InputBindings {
+	pub fn new() -> Self {
+		Self {
+			bindings: Action::ALL.into_iter().map(|action| (action, action.get_default_bindings())).collect(),
+		}
+	}
+	pub fn get(&self, action: Action) -> &[Binding] {
+		self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+	}
+	// puts `binding` in `slot` (or at the end if the action has fewer bindings) and takes it away from any actions it would conflict with
+	// returns the actions that lost the binding
+	pub fn set_binding(&mut self, action: Action, slot: usize, binding: Binding) -> Vec<Action> {
+		let mut conflicting_actions = vec!();
+		for other_action in Action::ALL {
+			if !action.conflicts_with(other_action) {continue;}
+			let Some(other_bindings) = self.bindings.get_mut(&other_action) else {continue;};
+			if let Some(index) = other_bindings.iter().position(|other_binding| *other_binding == binding) {
+				other_bindings.remove(index);
+				conflicting_actions.push(other_action);
+			}
+		}
+		let bindings = self.bindings.entry(action).or_default();
+		if let Some(index) = bindings.iter().position(|other_binding| *other_binding == binding) {
+			if index == slot {return conflicting_actions;}
+			bindings.remove(index);
+		}
+		if slot < bindings.len() {
+			bindings[slot] = binding;
+		} else {
+			bindings.push(binding);
+		}
+		conflicting_actions
+	}
+	pub fn remove_binding(&mut self, action: Action, slot: usize) -> Option<Binding> {
+		let bindings = self.bindings.get_mut(&action)?;
+		(slot < bindings.len()).then(|| bindings.remove(slot))
+	}
+}
+
+
+
+// this is kept next to the executable instead of in `assets` since it's written by the game (like the high scores)
+pub fn get_input_bindings_path() -> PathBuf {
+	get_program_file_path("input_bindings.txt")
+}
+
+
+
+// one `action: binding, binding, ...` per line, `//` starts a comment
+// actions that aren't in the file keep their default bindings, an action with nothing after the colon is unbound
+pub fn load_input_bindings(path: &Path) -> Result<InputBindings> {
+	let mut output = InputBindings::new();
+	
+	let file_string = fs::read_to_string(path).with_context(|| format!("Could not read input bindings file {path:?}"))?;
+	for line in gui::load::parse_key_value_lines(&file_string, path) {
+		let (i, field_name, field_value) = line?;
+		
+		let action = Action::from_name(field_name).ok_or_else(|| Error::msg(format!("Invalid action \"{field_name}\" in {path:?} line {i}")))?;
+		let mut bindings = vec!();
+		for binding_name in field_value.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+			let binding = parse_binding(binding_name).ok_or_else(|| Error::msg(format!("Invalid binding \"{binding_name}\" in {path:?} line {i}")))?;
+			if !bindings.contains(&binding) {
+				bindings.push(binding);
+			}
+		}
+		output.bindings.insert(action, bindings);
+		
+	}
+	
+	Ok(output)
+}
+
+
+
+pub fn save_input_bindings(path: &Path, input_bindings: &InputBindings) -> Result<()> {
+	let mut file_string = String::from("// action: binding, binding, ...\n// keys use their names (W, Up, Key1, Space, ...), mouse buttons are MouseLeft, MouseRight and MouseMiddle, and Ctrl+<key> needs ctrl held\n\n");
+	for action in Action::ALL {
+		let binding_names = input_bindings.get(action).iter().map(Binding::get_name).collect::<Vec<_>>();
+		file_string += &format!("{}: {}\n", action.get_name(), binding_names.join(", "));
+	}
+	fs::write(path, file_string).with_context(|| format!("Could not write input bindings file {path:?}"))
+}
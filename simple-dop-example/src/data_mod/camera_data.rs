@@ -0,0 +1,102 @@
+use crate::prelude::*;
+
+
+
+// how the square arena is placed in a window that usually isn't square
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraFitMode {
+	// the whole arena is shown, with bars on the long sides of the window
+	Fit,
+	// the arena covers the whole window, with the edges on the long sides cut off
+	Fill,
+	// the arena is stretched to the window's shape
+	Stretch,
+}
+
+impl CameraFitMode {
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"fit"     => Some(Self::Fit),
+			"fill"    => Some(Self::Fill),
+			"stretch" => Some(Self::Stretch),
+			_ => None,
+		}
+	}
+}
+
+
+
+// the camera's moving parts, everything that's tuned lives in `ProgramSettings` so that it can be reloaded
+// `trauma` goes from 0 to 1 and is added by hits and kills, the shake is trauma squared so small hits stay subtle
+#[derive(Debug, Clone)]
+pub struct Camera {
+	pub center: Vec2,
+	pub prev_center: Vec2,
+	pub trauma: f32,
+	pub prev_trauma: f32,
+	pub shake_time: f32,
+}
+
+impl Camera {
+	pub fn new() -> Self {
+		Self {
+			center: Vec2::splat(0.5),
+			prev_center: Vec2::splat(0.5),
+			trauma: 0.,
+			prev_trauma: 0.,
+			shake_time: 0.,
+		}
+	}
+	pub fn add_trauma(&mut self, amount: f32) {
+		self.trauma = (self.trauma + amount).min(1.);
+	}
+	// `center` is left unclamped here, since how much of the arena fits on screen depends on the window's shape
+	pub fn update(&mut self, settings: &ProgramSettings, target: Vec2, dt: f32) {
+		self.prev_center = self.center;
+		self.prev_trauma = self.trauma;
+		self.center = target;
+		self.trauma = (self.trauma - settings.camera_trauma_decay_per_sec * dt).max(0.);
+		self.shake_time += dt;
+	}
+	// the view that everything in the arena is drawn with
+	pub fn get_view(&self, settings: &ProgramSettings, tick_alpha: f32, screen_size: UVec2) -> ArenaView {
+		let mut output = self.get_steady_view(settings, tick_alpha, screen_size);
+		let shake_time = self.shake_time + tick_alpha * program_settings::TICK_DURATION_SECS;
+		let trauma = self.prev_trauma + (self.trauma - self.prev_trauma) * tick_alpha;
+		// a few out-of-step waves per axis look random enough without needing an rng
+		let t = shake_time * settings.camera_shake_frequency;
+		let noise = Vec2::new(
+			((t * 1.0).sin() + (t * 2.3 + 1.7).sin() * 0.5) / 1.5,
+			((t * 1.3 + 0.6).sin() + (t * 2.9 + 3.1).sin() * 0.5) / 1.5,
+		);
+		output.center += noise * settings.camera_shake_max_offset * trauma * trauma;
+		output
+	}
+	// same as `get_view()` but without the shake, so that aiming doesn't jitter
+	// follows the center on each axis that doesn't fit on screen, without ever showing anything past the arena's edges
+	pub fn get_steady_view(&self, settings: &ProgramSettings, tick_alpha: f32, screen_size: UVec2) -> ArenaView {
+		let mut output = ArenaView {
+			center: self.prev_center.lerp(self.center, tick_alpha),
+			zoom: settings.camera_zoom,
+			fit_mode: settings.camera_fit_mode,
+			screen_size,
+		};
+		let half_view_size = screen_size.as_vec2() * 0.5 / output.get_scale();
+		output.center = Vec2::new(
+			clamp_view_center(output.center.x, half_view_size.x),
+			clamp_view_center(output.center.y, half_view_size.y),
+		);
+		output
+	}
+}
+
+
+
+// an axis that shows the whole arena (or more) stays centered
+fn clamp_view_center(center: f32, half_view_size: f32) -> f32 {
+	if half_view_size >= 0.5 {
+		0.5
+	} else {
+		center.clamp(half_view_size, 1. - half_view_size)
+	}
+}
@@ -0,0 +1,117 @@
+use crate::prelude::*;
+
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Difficulty {
+	Easy,
+	#[default]
+	Normal,
+	Hard,
+	// uses the `custom difficulty ...` values from the settings file
+	Custom,
+}
+
+impl Difficulty {
+	pub const ALL: [Self; 4] = [Self::Easy, Self::Normal, Self::Hard, Self::Custom];
+	pub fn get_name(&self) -> &'static str {
+		match self {
+			Self::Easy => "Easy",
+			Self::Normal => "Normal",
+			Self::Hard => "Hard",
+			Self::Custom => "Custom",
+		}
+	}
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|difficulty| difficulty.get_name() == name)
+	}
+	// used for the replay files, so these can't change
+	pub fn to_id(self) -> u8 {
+		match self {
+			Self::Easy => 0,
+			Self::Normal => 1,
+			Self::Hard => 2,
+			Self::Custom => 3,
+		}
+	}
+	pub fn from_id(id: u8) -> Option<Self> {
+		Self::ALL.into_iter().find(|difficulty| difficulty.to_id() == id)
+	}
+	// `offset` is usually 1 or -1, wraps around at both ends
+	pub fn get_offset(self, offset: isize) -> Self {
+		let index = Self::ALL.iter().position(|difficulty| *difficulty == self).unwrap_or(0);
+		let new_index = (index as isize + offset).rem_euclid(Self::ALL.len() as isize);
+		Self::ALL[new_index as usize]
+	}
+	pub fn get_modifiers(self, settings: &ProgramSettings) -> DifficultyModifiers {
+		self.get_builtin_modifiers().unwrap_or_else(|| settings.custom_difficulty.clone())
+	}
+	// `None` for custom, since those come from the settings
+	pub fn get_builtin_modifiers(self) -> Option<DifficultyModifiers> {
+		match self {
+			Self::Easy => Some(DifficultyModifiers {
+				enemy_speed_coef: 0.8,
+				enemy_fire_rate_coef: 0.7,
+				enemy_spawn_acceleration_coef: 0.5,
+				enemy_bullet_speed_coef: 0.8,
+				player_health_coef: 1.5,
+			}),
+			Self::Normal => Some(DifficultyModifiers::new()),
+			Self::Hard => Some(DifficultyModifiers {
+				enemy_speed_coef: 1.25,
+				enemy_fire_rate_coef: 1.4,
+				enemy_spawn_acceleration_coef: 1.5,
+				enemy_bullet_speed_coef: 1.2,
+				player_health_coef: 0.75,
+			}),
+			Self::Custom => None,
+		}
+	}
+}
+
+
+
+// everything is a multiplier on the loaded values, so 1 everywhere is the same as no difficulty at all
+#[derive(Debug, Clone, PartialEq)]
+pub struct DifficultyModifiers {
+	pub enemy_speed_coef: f32,
+	// higher means enemies shoot more often
+	pub enemy_fire_rate_coef: f32,
+	// scales how much faster endless mode spawns enemies after each spawn
+	pub enemy_spawn_acceleration_coef: f32,
+	pub enemy_bullet_speed_coef: f32,
+	pub player_health_coef: f32,
+}
+
+impl DifficultyModifiers {
+	pub fn new() -> Self {
+		Self {
+			enemy_speed_coef: 1.,
+			enemy_fire_rate_coef: 1.,
+			enemy_spawn_acceleration_coef: 1.,
+			enemy_bullet_speed_coef: 1.,
+			player_health_coef: 1.,
+		}
+	}
+	pub fn apply_to_archetype(&self, archetype: &EnemyArchetype) -> EnemyArchetype {
+		let mut output = archetype.clone();
+		output.speed *= self.enemy_speed_coef;
+		output.shoot_wait_secs /= self.enemy_fire_rate_coef;
+		output.bullet_speed *= self.enemy_bullet_speed_coef;
+		output
+	}
+	// `LoopingTimer` can only loop once per tick, so a high fire rate can't bring the shoot wait below that
+	pub fn check_archetype(&self, archetype: &EnemyArchetype) -> Result<()> {
+		let shoot_wait_secs = self.apply_to_archetype(archetype).shoot_wait_secs;
+		if shoot_wait_secs < program_settings::TICK_DURATION_SECS {
+			return Err(Error::msg(format!("Enemy archetype \"{}\" would have a shoot wait of {shoot_wait_secs} secs, which is shorter than one tick ({} secs)", archetype.name, program_settings::TICK_DURATION_SECS)));
+		}
+		Ok(())
+	}
+	// the spawn wait is multiplied by `coef_percent / 100` after every spawn, this scales how far that is from 100
+	pub fn apply_to_spawn_wait_coef_percent(&self, coef_percent: u32) -> u32 {
+		let acceleration = 100. - coef_percent as f32;
+		let output = 100. - (acceleration * self.enemy_spawn_acceleration_coef).round();
+		output.max(1.) as u32
+	}
+}
@@ -0,0 +1,233 @@
+use crate::prelude::*;
+use std::fs;
+
+
+
+// everything that makes one type of enemy different from another, loaded from `assets/enemies/<name>.txt`
+#[derive(Debug, Clone)]
+pub struct EnemyArchetype {
+	pub name: String,
+	pub size: f32,
+	pub speed: f32,
+	pub health: f32,
+	pub texture_name: String,
+	pub movement_style: MovementStyle,
+	pub firing_pattern: FiringPattern,
+	pub shoot_wait_secs: f32,
+	pub bullet_speed: f32,
+	pub bullet_damage: f32,
+	// damage dealt by touching the player
+	pub contact_damage: f32,
+	pub score_value: usize,
+}
+
+impl EnemyArchetype {
+	// values for any keys that the file leaves out
+	pub fn new(name: impl Into<String>) -> Self {
+		Self {
+			name: name.into(),
+			size: 0.05,
+			speed: 0.2,
+			health: 1.,
+			texture_name: String::from("enemy"),
+			movement_style: MovementStyle::Bounce,
+			firing_pattern: FiringPattern::Aimed,
+			shoot_wait_secs: 1.,
+			bullet_speed: 0.6,
+			bullet_damage: 0.25,
+			contact_damage: 0.2,
+			score_value: 100,
+		}
+	}
+	// see `replay_data::get_simulation_hash()`, the texture is left out since it only changes what's drawn
+	pub fn write_simulation_fields(&self, hasher: &mut StableHasher) {
+		hasher.write_str(&self.name);
+		hasher.write_f32(self.size);
+		hasher.write_f32(self.speed);
+		hasher.write_f32(self.health);
+		hasher.write_u64(match self.movement_style {
+			MovementStyle::Bounce => 0,
+			MovementStyle::Chase => 1,
+			MovementStyle::Stationary => 2,
+		});
+		match self.firing_pattern {
+			FiringPattern::None => hasher.write_u64(0),
+			FiringPattern::Aimed => hasher.write_u64(1),
+			FiringPattern::AimedWithLead => hasher.write_u64(2),
+			FiringPattern::Spread {bullet_count, angle_degrees} => {
+				hasher.write_u64(3);
+				hasher.write_u64(bullet_count as u64);
+				hasher.write_f32(angle_degrees);
+			}
+			FiringPattern::Spiral {arm_count, degrees_per_shot} => {
+				hasher.write_u64(4);
+				hasher.write_u64(arm_count as u64);
+				hasher.write_f32(degrees_per_shot);
+			}
+			FiringPattern::Burst {bullet_count, interval_secs} => {
+				hasher.write_u64(5);
+				hasher.write_u64(bullet_count as u64);
+				hasher.write_f32(interval_secs);
+			}
+			FiringPattern::Ring {bullet_count} => {
+				hasher.write_u64(6);
+				hasher.write_u64(bullet_count as u64);
+			}
+		}
+		hasher.write_f32(self.shoot_wait_secs);
+		hasher.write_f32(self.bullet_speed);
+		hasher.write_f32(self.bullet_damage);
+		hasher.write_f32(self.contact_damage);
+		hasher.write_u64(self.score_value as u64);
+	}
+}
+
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MovementStyle {
+	// moves in a straight line at a random angle and bounces off the walls
+	Bounce,
+	// always moves towards the player
+	Chase,
+	Stationary,
+}
+
+
+
+// describes what an enemy fires every time its shoot timer loops, see `bullet_patterns::update_enemy_firing()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FiringPattern {
+	None,
+	// one bullet straight at the player
+	Aimed,
+	// one bullet at where the player will be if they keep moving the same way
+	AimedWithLead,
+	// fan of bullets centered on the player
+	Spread {bullet_count: usize, angle_degrees: f32},
+	// evenly spaced arms that rotate a bit more with every shot
+	Spiral {arm_count: usize, degrees_per_shot: f32},
+	// several aimed bullets in quick succession
+	Burst {bullet_count: usize, interval_secs: f32},
+	// bullets in every direction
+	Ring {bullet_count: usize},
+}
+
+
+
+// per-enemy state needed by some firing patterns
+#[derive(Debug, Clone, Default)]
+pub struct FiringState {
+	pub angle: f32,
+	pub burst_shots_left: usize,
+	pub burst_timer: f32,
+}
+
+
+
+pub fn get_enemy_archetypes_path() -> PathBuf {
+	get_program_file_path("assets/enemies")
+}
+
+
+
+// loads every `.txt` file in the folder, the file name is the archetype's name
+// the output is sorted by name so that archetype indices don't depend on the filesystem
+pub fn load_enemy_archetypes(folder: impl AsRef<Path>) -> Result<Vec<EnemyArchetype>> {
+	let folder = folder.as_ref();
+	let mut output = vec!();
+	let mut errors = vec!();
+	for entry in fs::read_dir(folder).with_context(|| format!("Could not read enemy archetypes folder {folder:?}"))? {
+		let path = entry?.path();
+		if gui::load::get_file_extension(&path).ok() != Some("txt") {continue;}
+		match load_enemy_archetype(&path) {
+			Result::Ok (archetype) => output.push(archetype),
+			Result::Err (err) => errors.push(err),
+		}
+	}
+	if !errors.is_empty() {
+		return Err(PackagedErrors::new(errors).into());
+	}
+	output.sort_by(|a, b| a.name.cmp(&b.name));
+	Ok(output)
+}
+
+
+
+// same layout as the gui files: one `key: value` per line, `//` starts a comment
+pub fn load_enemy_archetype(path: &Path) -> Result<EnemyArchetype> {
+	let name = gui::load::get_file_name(path)?;
+	let mut output = EnemyArchetype::new(name);
+	
+	// the firing pattern's parameters can come before or after `firing`, so it's only built once the whole file is read
+	let mut firing = None;
+	let mut bullet_count = 5;
+	let mut spread_angle = 60.;
+	let mut spin = 15.;
+	let mut burst_interval = 0.1;
+	
+	let file_string = fs::read_to_string(path).with_context(|| format!("Could not read enemy archetype file {path:?}"))?;
+	for line in gui::load::parse_key_value_lines(&file_string, path) {
+		let (i, field_name, field_value) = line?;
+		
+		match field_name {
+			"size"       => output.size            = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"speed"      => output.speed           = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"health"     => output.health          = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"texture"    => output.texture_name    = field_value.to_string(),
+			"movement"   => output.movement_style  = parse_value_to_movement_style(field_value, i, path)?,
+			"firing"     => firing                 = Some((field_value, i)),
+			"shoot wait" => output.shoot_wait_secs = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"bullet speed" => output.bullet_speed = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"bullet damage" => output.bullet_damage = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"contact damage" => output.contact_damage = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"score"      => output.score_value     = field_value.parse().with_context(|| format!("Invalid score \"{field_value}\" in {path:?} line {i}"))?,
+			"bullet count"   => bullet_count   = field_value.parse().with_context(|| format!("Invalid bullet count \"{field_value}\" in {path:?} line {i}"))?,
+			"spread angle"   => spread_angle   = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"spin"           => spin           = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"burst interval" => burst_interval = gui::load::parse_value_to_f32(field_value, i, path)?,
+			_ => return Err(Error::msg(format!("Invalid enemy archetype field \"{field_name}\" in {path:?} line {i}"))),
+		}
+		
+	}
+	
+	if let Some((value, line)) = firing {
+		output.firing_pattern = match &*value.to_lowercase() {
+			"none" => FiringPattern::None,
+			"aimed" => FiringPattern::Aimed,
+			"aimed with lead" => FiringPattern::AimedWithLead,
+			"spread" => FiringPattern::Spread {bullet_count, angle_degrees: spread_angle},
+			"spiral" => FiringPattern::Spiral {arm_count: bullet_count, degrees_per_shot: spin},
+			"burst" => FiringPattern::Burst {bullet_count, interval_secs: burst_interval},
+			"ring" => FiringPattern::Ring {bullet_count},
+			_ => return Err(Error::msg(format!("Invalid firing pattern \"{value}\" in {path:?} line {line} (expected none, aimed, aimed with lead, spread, spiral, burst, or ring)"))),
+		};
+	}
+	
+	// `LoopingTimer` can only loop once per tick
+	if output.shoot_wait_secs < program_settings::TICK_DURATION_SECS {
+		return Err(Error::msg(format!("Enemy archetype {path:?} must have a shoot wait of at least one tick ({} secs)", program_settings::TICK_DURATION_SECS)));
+	}
+	// the custom difficulty is checked when the settings are loaded
+	for modifiers in Difficulty::ALL.into_iter().filter_map(Difficulty::get_builtin_modifiers) {
+		modifiers.check_archetype(&output).with_context(|| format!("Invalid shoot wait in {path:?}"))?;
+	}
+	Ok(output)
+}
+
+
+
+pub fn parse_value_to_movement_style(value: &str, line: usize, path: &Path) -> Result<MovementStyle> {
+	match &*value.to_lowercase() {
+		"bounce" => Ok(MovementStyle::Bounce),
+		"chase" => Ok(MovementStyle::Chase),
+		"stationary" => Ok(MovementStyle::Stationary),
+		_ => Err(Error::msg(format!("Invalid movement style \"{value}\" in {path:?} line {line} (expected bounce, chase, or stationary)"))),
+	}
+}
+
+
+
+pub fn get_enemy_archetype_index(archetypes: &[EnemyArchetype], name: &str) -> Option<usize> {
+	archetypes.iter().position(|archetype| archetype.name == name)
+}
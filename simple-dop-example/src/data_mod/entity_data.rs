@@ -0,0 +1,145 @@
+use crate::prelude::*;
+
+
+
+// stable reference to an entity, stays valid no matter how the entity's dense index changes
+// once the entity is removed the slot's generation changes, so old handles stop resolving
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EntityHandle {
+	pub slot: u32,
+	pub generation: u32,
+}
+
+
+
+#[derive(Debug)]
+pub struct EntitySlot {
+	pub generation: u32,
+	pub dense_index: Option<usize>,
+}
+
+
+
+// generational index arena that sits alongside a struct-of-arrays container (like `Enemies`)
+// the container keeps its columns dense and tells the arena whenever it pushes or swap-removes
+#[derive(Debug)]
+pub struct EntityArena {
+	pub slots: Vec<EntitySlot>,
+	pub free_slots: Vec<u32>,
+	// dense index -> handle, always the same length as the container's columns
+	pub handles: Vec<EntityHandle>,
+}
+
+impl EntityArena {
+	pub fn empty() -> Self {
+		Self {
+			slots: vec!(),
+			free_slots: vec!(),
+			handles: vec!(),
+		}
+	}
+	
+	// call when pushing onto the end of the container's columns
+	pub fn insert(&mut self) -> EntityHandle {
+		let dense_index = self.handles.len();
+		let slot = match self.free_slots.pop() {
+			Some(slot) => slot,
+			None => {
+				self.slots.push(EntitySlot {generation: 0, dense_index: None});
+				(self.slots.len() - 1) as u32
+			}
+		};
+		let slot_data = &mut self.slots[slot as usize];
+		slot_data.dense_index = Some(dense_index);
+		let handle = EntityHandle {slot, generation: slot_data.generation};
+		self.handles.push(handle);
+		handle
+	}
+	
+	// call when swap-removing from the container's columns
+	pub fn swap_remove(&mut self, dense_index: usize) {
+		let removed_handle = self.handles.swap_remove(dense_index);
+		let removed_slot = &mut self.slots[removed_handle.slot as usize];
+		removed_slot.generation = removed_slot.generation.wrapping_add(1);
+		removed_slot.dense_index = None;
+		self.free_slots.push(removed_handle.slot);
+		// the last entity was moved into the gap
+		if let Some(moved_handle) = self.handles.get(dense_index) {
+			self.slots[moved_handle.slot as usize].dense_index = Some(dense_index);
+		}
+	}
+	
+	// returns None if the handle is stale (its entity has been removed)
+	pub fn get_dense_index(&self, handle: EntityHandle) -> Option<usize> {
+		let slot = self.slots.get(handle.slot as usize)?;
+		if slot.generation != handle.generation {return None;}
+		slot.dense_index
+	}
+	
+	pub fn contains(&self, handle: EntityHandle) -> bool {
+		self.get_dense_index(handle).is_some()
+	}
+	
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use notan::random::rand::{SeedableRng, rngs::StdRng};
+	
+	#[test]
+	fn reused_slot_rejects_old_handle() {
+		let mut arena = EntityArena::empty();
+		let old_handle = arena.insert();
+		arena.swap_remove(0);
+		let new_handle = arena.insert();
+		assert_eq!(new_handle.slot, old_handle.slot);
+		assert_ne!(new_handle.generation, old_handle.generation);
+		assert!(!arena.contains(old_handle));
+		assert_eq!(arena.get_dense_index(old_handle), None);
+		assert_eq!(arena.get_dense_index(new_handle), Some(0));
+	}
+	
+	#[test]
+	fn handle_survives_swap_remove_of_another_entity() {
+		let mut arena = EntityArena::empty();
+		let first = arena.insert();
+		let second = arena.insert();
+		let third = arena.insert();
+		// removing the first entity moves the last one into its dense index
+		arena.swap_remove(0);
+		assert!(!arena.contains(first));
+		assert_eq!(arena.get_dense_index(third), Some(0));
+		assert_eq!(arena.get_dense_index(second), Some(1));
+		assert_eq!(arena.handles, vec!(third, second));
+	}
+	
+	#[test]
+	fn remove_returns_false_for_stale_handles() {
+		let mut rng = StdRng::seed_from_u64(0);
+		let archetype = EnemyArchetype::new("test");
+		let mut enemies = Enemies::empty();
+		let enemy_a = enemies.push(Enemy::new(0, &archetype, &mut rng));
+		let enemy_b = enemies.push(Enemy::new(0, &archetype, &mut rng));
+		assert!(enemies.remove(enemy_a));
+		assert!(!enemies.remove(enemy_a));
+		let enemy_c = enemies.push(Enemy::new(0, &archetype, &mut rng));
+		assert!(!enemies.remove(enemy_a));
+		assert_eq!(enemies.len(), 2);
+		assert!(enemies.remove(enemy_b));
+		assert!(enemies.remove(enemy_c));
+		
+		let mut bullets = Bullets::empty();
+		let bullet_a = bullets.push(Bullet::new(Vec2::ZERO, Vec2::ONE, 1., 1.));
+		let bullet_b = bullets.push(Bullet::new(Vec2::ONE, Vec2::ZERO, 1., 1.));
+		assert!(bullets.remove(bullet_a));
+		assert!(!bullets.remove(bullet_a));
+		bullets.push(Bullet::new(Vec2::ZERO, Vec2::ONE, 1., 1.));
+		assert!(!bullets.remove(bullet_a));
+		assert!(bullets.remove(bullet_b));
+		assert_eq!(bullets.len(), 1);
+	}
+	
+}
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use crate::prelude::*;
-use notan::random::rand::thread_rng;
+use notan::random::rand::{thread_rng, Rng, SeedableRng, rngs::StdRng};
 
 
 
@@ -17,6 +17,14 @@ pub struct ProgramData {
 	pub mode: ProgramMode,
 	pub main_menu_data: MainMenuData,
 	pub playing_data: PlayingData,
+	pub replay_data: ReplayData,
+	pub game_over_data: GameOverData,
+	pub name_entry_data: NameEntryData,
+	pub high_scores_data: HighScoresData,
+	pub options_data: OptionsData,
+	
+	pub high_scores: Vec<HighScoreEntry>,
+	pub input_bindings: InputBindings,
 	
 }
 
@@ -24,9 +32,11 @@ pub struct ProgramData {
 
 pub struct Textures {
 	pub player: Texture,
-	pub enemy: Texture,
 	pub player_bullet: Texture,
 	pub enemy_bullet: Texture,
+	// keyed by `EnemyArchetype::texture_name`
+	pub enemies: HashMap<String, Texture>,
+	pub pickups: HashMap<PickupKind, Texture>,
 }
 
 
@@ -34,6 +44,11 @@ pub struct Textures {
 pub enum ProgramMode {
 	MainMenu,
 	Playing,
+	Replay,
+	GameOver,
+	NameEntry,
+	HighScores,
+	Options,
 }
 
 
@@ -62,16 +77,71 @@ pub struct PlayingData {
 	pub gui: GuiElement<CustomGuiData>,
 	pub start_time: Instant,
 	pub pause_data: PauseData,
+	pub tick_accumulator: f32,
+	pub tick_count: usize,
+	
+	// all gameplay randomness has to go through `rng` so that runs can be reproduced from `seed`
+	pub seed: u64,
+	pub seed_override: Option<u64>,
+	pub rng: StdRng,
+	// cosmetic randomness gets its own rng so that particles can never change how a run plays out
+	pub particle_rng: StdRng,
+	
+	// inputs are gathered every frame but only applied (and recorded) once per tick
+	pub pending_tick_input: TickInput,
+	pub recorded_tick_inputs: Vec<TickInput>,
 	
 	pub player_pos: Vec2,
+	pub prev_player_pos: Vec2,
 	pub player_vel: Vec2,
 	pub player_health: f32,
+	pub player_max_health: f32,
+	pub invincibility_secs_left: f32,
+	pub time_since_damage: f32,
+	pub active_effects: ActiveEffects,
+	pub weapon_index: usize,
+	pub unlocked_weapons: Vec<bool>,
+	pub fire_cooldown: f32,
+	// the real state of the fire button, `pending_tick_input.fire_held` stays set until a tick sees it
+	pub fire_button_is_down: bool,
 	pub score: usize,
+	pub kills: usize,
+	pub combo_multiplier: f32,
+	pub time_since_last_kill: f32,
+	pub survival_score_timer: LoopingTimer,
+	
+	// `selected_level` is what the player picked, `level` is what the current run is using (None means endless mode)
+	pub selected_level: Option<Level>,
+	pub level: Option<Level>,
+	pub wave_state: WaveState,
 	
-	pub enemies: Vec<Enemy>,
+	// `difficulty` is what the player picked, `difficulty_modifiers` is what the current run is using
+	pub difficulty: Difficulty,
+	pub difficulty_modifiers: DifficultyModifiers,
+	
+	// `loaded_enemy_archetypes` are the archetypes from `assets/enemies`, `enemy_archetypes` are those with the difficulty applied
+	pub loaded_enemy_archetypes: Vec<EnemyArchetype>,
+	pub enemy_archetypes: Vec<EnemyArchetype>,
+	pub weapons: Vec<WeaponDefinition>,
+	// `loaded_settings` is what's in `assets/settings.txt`, `settings` is what the current run is using
+	// reloading only changes `loaded_settings`, so that a run (or a replay) never changes settings partway through
+	pub loaded_settings: ProgramSettings,
+	pub settings: ProgramSettings,
+	pub enemies: Enemies,
 	pub enemy_spawn_timer: LoopingTimer,
-	pub player_bullets: Vec<Bullet>,
-	pub enemy_bullets: Vec<Bullet>,
+	pub enemy_spawn_wait_coef_percent: u32,
+	pub player_bullets: Bullets,
+	pub enemy_bullets: Bullets,
+	pub spatial_hash: SpatialHash,
+	// scratch buffer for `player_bullet_collision()`, kept around to avoid allocating every tick
+	pub enemy_is_dead: Vec<bool>,
+	// same for the (pos, bullet vel) of hit enemies and (archetype, pos) of killed enemies, cleared at the start of every tick
+	pub hit_enemies: Vec<(Vec2, Vec2)>,
+	pub killed_enemies: Vec<(usize, Vec2)>,
+	pub pickups: Pickups,
+	pub particle_emitters: HashMap<ParticleEffect, ParticleEmitter>,
+	pub particles: Particles,
+	pub camera: Camera,
 	
 }
 
@@ -82,17 +152,61 @@ impl PlayingData {
 			gui: GuiElement::new("", vec!(), HashMap::new(), &mut CustomGuiData::default),
 			start_time: Instant::now(),
 			pause_data: PauseData::new(),
+			tick_accumulator: 0.,
+			tick_count: 0,
+			
+			seed: 0,
+			seed_override: None,
+			rng: StdRng::seed_from_u64(0),
+			particle_rng: StdRng::seed_from_u64(0),
+			
+			pending_tick_input: TickInput::default(),
+			recorded_tick_inputs: vec!(),
 			
 			player_pos: Vec2::default(),
+			prev_player_pos: Vec2::default(),
 			player_vel: Vec2::default(),
 			player_health: 0.,
+			player_max_health: 0.,
+			invincibility_secs_left: 0.,
+			time_since_damage: 0.,
+			active_effects: ActiveEffects::default(),
+			weapon_index: 0,
+			unlocked_weapons: vec!(),
+			fire_cooldown: 0.,
+			fire_button_is_down: false,
 			score: 0,
+			kills: 0,
+			combo_multiplier: 1.,
+			time_since_last_kill: 0.,
+			survival_score_timer: LoopingTimer::new(Duration::from_secs(0)),
+			
+			selected_level: None,
+			level: None,
+			wave_state: WaveState::new(),
 			
-			enemies: vec!(),
+			difficulty: Difficulty::Normal,
+			difficulty_modifiers: DifficultyModifiers::new(),
+			
+			loaded_enemy_archetypes: vec!(),
+			enemy_archetypes: vec!(),
+			weapons: vec!(),
+			loaded_settings: ProgramSettings::new(),
+			settings: ProgramSettings::new(),
+			enemies: Enemies::empty(),
 			enemy_spawn_timer: LoopingTimer::new(Duration::from_secs(0)),
+			enemy_spawn_wait_coef_percent: 100,
 			
-			player_bullets: vec!(),
-			enemy_bullets: vec!(),
+			player_bullets: Bullets::empty(),
+			enemy_bullets: Bullets::empty(),
+			spatial_hash: SpatialHash::new(program_settings::SPATIAL_HASH_CELLS_PER_SIDE),
+			enemy_is_dead: vec!(),
+			hit_enemies: vec!(),
+			killed_enemies: vec!(),
+			pickups: Pickups::empty(),
+			particle_emitters: HashMap::new(),
+			particles: Particles::with_capacity(program_settings::MAX_PARTICLES),
+			camera: Camera::new(),
 			
 		}
 	}
@@ -100,18 +214,181 @@ impl PlayingData {
 		
 		self.start_time = Instant::now();
 		self.pause_data = PauseData::new();
+		self.tick_accumulator = 0.;
+		self.tick_count = 0;
+		
+		self.seed = self.seed_override.unwrap_or_else(|| thread_rng().gen());
+		self.rng = StdRng::seed_from_u64(self.seed);
+		self.particle_rng = StdRng::seed_from_u64(self.seed);
+		
+		self.settings = self.loaded_settings.clone();
+		self.difficulty_modifiers = self.difficulty.get_modifiers(&self.settings);
+		let modifiers = &self.difficulty_modifiers;
+		self.enemy_archetypes = self.loaded_enemy_archetypes.iter().map(|archetype| modifiers.apply_to_archetype(archetype)).collect();
+		self.enemy_spawn_wait_coef_percent = modifiers.apply_to_spawn_wait_coef_percent(self.settings.enemy_spawn_wait_coef_percent);
+		self.player_max_health = self.settings.player_max_health * modifiers.player_health_coef;
+		
+		self.pending_tick_input = TickInput::default();
+		self.recorded_tick_inputs = vec!();
 		
 		self.player_pos = Vec2::new(0.5, 0.5);
+		self.prev_player_pos = self.player_pos;
 		self.player_vel = Vec2::new(0., 0.);
-		self.player_health = 1.;
+		self.player_health = self.player_max_health;
+		self.invincibility_secs_left = 0.;
+		self.time_since_damage = 0.;
+		self.active_effects = ActiveEffects::default();
+		self.weapon_index = get_weapon_index(&self.weapons, &self.settings.starting_weapon).unwrap_or(0);
+		self.unlocked_weapons = vec!(false; self.weapons.len());
+		if let Some(is_unlocked) = self.unlocked_weapons.get_mut(self.weapon_index) {
+			*is_unlocked = true;
+		}
+		self.fire_cooldown = 0.;
+		self.fire_button_is_down = false;
 		self.score = 0;
+		self.kills = 0;
+		self.combo_multiplier = 1.;
+		self.time_since_last_kill = 0.;
+		self.survival_score_timer = LoopingTimer::new(Duration::from_secs_f32(self.settings.survival_score_interval_secs));
+		
+		self.level = self.selected_level.clone();
+		self.wave_state = WaveState::new();
 		
-		self.enemies = vec!();
-		self.enemy_spawn_timer = LoopingTimer::new(Duration::from_secs_f32(program_settings::ENEMY_SPAWN_WAIT_SECS));
-		self.player_bullets = vec!();
-		self.enemy_bullets = vec!();
+		self.enemies = Enemies::empty();
+		self.enemy_spawn_timer = LoopingTimer::new(Duration::from_secs_f32(self.settings.enemy_spawn_wait_secs));
+		self.player_bullets = Bullets::empty();
+		self.enemy_bullets = Bullets::empty();
+		self.pickups = Pickups::empty();
+		self.particles.clear();
+		self.camera = Camera::new();
 		
 	}
+	pub fn is_run_over(&self) -> bool {
+		self.player_health <= 0. || self.wave_state.is_finished
+	}
+	// how far the current frame is between the last tick and the next one, for render interpolation
+	pub fn get_tick_alpha(&self) -> f32 {
+		self.tick_accumulator / program_settings::TICK_DURATION_SECS
+	}
+	pub fn get_time_survived(&self) -> Duration {
+		Duration::from_secs_f32(program_settings::TICK_DURATION_SECS) * self.tick_count as u32
+	}
+}
+
+
+
+pub struct ReplayData {
+	pub gui: GuiElement<CustomGuiData>,
+	pub replay: Replay,
+	pub next_tick_index: usize,
+	// `aim_target` is only recorded while firing, so this is what the crosshair shows between shots
+	pub last_fired_aim_target: Option<Vec2>,
+	pub is_paused: bool,
+	pub is_fast_forwarding: bool,
+	pub step_requested: bool,
+}
+
+impl ReplayData {
+	pub fn empty() -> Self {
+		Self {
+			gui: GuiElement::new("", vec!(), HashMap::new(), &mut CustomGuiData::default),
+			replay: Replay {seed: 0, level_name: None, difficulty: Difficulty::Normal, simulation_hash: 0, custom_difficulty: DifficultyModifiers::new(), tick_inputs: vec!()},
+			next_tick_index: 0,
+			last_fired_aim_target: None,
+			is_paused: false,
+			is_fast_forwarding: false,
+			step_requested: false,
+		}
+	}
+}
+
+
+
+pub struct GameOverData {
+	pub gui: GuiElement<CustomGuiData>,
+	pub time_survived: Duration,
+	pub score: usize,
+	pub kills: usize,
+	pub level_complete: bool,
+}
+
+impl GameOverData {
+	pub fn empty() -> Self {
+		Self {
+			gui: GuiElement::new("", vec!(), HashMap::new(), &mut CustomGuiData::default),
+			time_survived: Duration::ZERO,
+			score: 0,
+			kills: 0,
+			level_complete: false,
+		}
+	}
+}
+
+
+
+pub struct NameEntryData {
+	pub gui: GuiElement<CustomGuiData>,
+	pub pending_entry: HighScoreEntry,
+}
+
+impl NameEntryData {
+	pub fn empty() -> Self {
+		Self {
+			gui: GuiElement::new("", vec!(), HashMap::new(), &mut CustomGuiData::default),
+			pending_entry: HighScoreEntry {
+				name: String::new(),
+				score: 0,
+				date: String::new(),
+				seed: 0,
+				duration: Duration::ZERO,
+				difficulty: Difficulty::Normal,
+			},
+		}
+	}
+}
+
+
+
+pub struct HighScoresData {
+	pub gui: GuiElement<CustomGuiData>,
+}
+
+impl HighScoresData {
+	pub fn empty() -> Self {
+		Self {
+			gui: GuiElement::new("", vec!(), HashMap::new(), &mut CustomGuiData::default),
+		}
+	}
+}
+
+
+
+pub struct OptionsData {
+	pub gui: GuiElement<CustomGuiData>,
+	// the options screen can be opened from the pause menu, in which case it goes back to the (still paused) game
+	pub return_to_playing: bool,
+	pub page: usize,
+	// the action and binding slot that's waiting for a key or mouse button
+	pub rebinding: Option<(Action, usize)>,
+	pub status: String,
+}
+
+impl OptionsData {
+	pub fn empty() -> Self {
+		Self {
+			gui: GuiElement::new("", vec!(), HashMap::new(), &mut CustomGuiData::default),
+			return_to_playing: false,
+			page: 0,
+			rebinding: None,
+			status: String::new(),
+		}
+	}
+	pub fn reset(&mut self, return_to_playing: bool) {
+		self.return_to_playing = return_to_playing;
+		self.page = 0;
+		self.rebinding = None;
+		self.status = String::from("Click a binding to change it");
+	}
 }
 
 
@@ -135,20 +412,28 @@ impl PauseData {
 
 
 #[derive(Debug)]
+// a single enemy, only used when spawning (see `Enemies` for how they're stored)
 pub struct Enemy {
+	pub archetype: usize,
 	pub pos: Vec2,
 	pub vel: Vec2,
+	pub size: f32,
+	pub health: f32,
 	pub shoot_timer: LoopingTimer,
 }
 
 impl Enemy {
-	pub fn new() -> Self {
-		let (pos_x, pos_y) = thread_rng().gen();
-		let (vel_x, vel_y) = thread_rng().gen();
-		let timer = LoopingTimer::new(Duration::from_secs_f32(program_settings::ENEMY_SHOOT_WAIT_SECS));
+	// `archetype_index` is the index of `archetype` in `PlayingData::enemy_archetypes`
+	pub fn new(archetype_index: usize, archetype: &EnemyArchetype, rng: &mut impl Rng) -> Self {
+		let (pos_x, pos_y) = rng.gen();
+		let (vel_x, vel_y) = rng.gen();
+		let timer = LoopingTimer::new(Duration::from_secs_f32(archetype.shoot_wait_secs));
 		Self {
+			archetype: archetype_index,
 			pos: Vec2::new(pos_x, pos_y),
-			vel: Vec2::new(vel_x, vel_y).normalize() * program_settings::ENEMY_SPEED,
+			vel: Vec2::new(vel_x, vel_y).normalize() * archetype.speed,
+			size: archetype.size,
+			health: archetype.health,
 			shoot_timer: timer,
 		}
 	}
@@ -156,19 +441,157 @@ impl Enemy {
 
 
 
+// struct-of-arrays storage, every column always has the same length and index `i` in each column is the same enemy
+// removal is swap-remove, so indices are NOT stable across removals, use the handles from `entities` to refer to an enemy across ticks
+pub struct Enemies {
+	pub entities: EntityArena,
+	pub archetype: Vec<usize>,
+	pub pos: Vec<Vec2>,
+	pub prev_pos: Vec<Vec2>,
+	pub vel: Vec<Vec2>,
+	pub size: Vec<f32>,
+	pub health: Vec<f32>,
+	pub shoot_timer: Vec<LoopingTimer>,
+	pub firing_state: Vec<FiringState>,
+}
+
+impl Enemies {
+	pub fn empty() -> Self {
+		Self {
+			entities: EntityArena::empty(),
+			archetype: vec!(),
+			pos: vec!(),
+			prev_pos: vec!(),
+			vel: vec!(),
+			size: vec!(),
+			health: vec!(),
+			shoot_timer: vec!(),
+			firing_state: vec!(),
+		}
+	}
+	pub fn len(&self) -> usize {
+		self.pos.len()
+	}
+	pub fn is_empty(&self) -> bool {
+		self.pos.is_empty()
+	}
+	pub fn push(&mut self, enemy: Enemy) -> EntityHandle {
+		self.archetype.push(enemy.archetype);
+		self.pos.push(enemy.pos);
+		self.prev_pos.push(enemy.pos);
+		self.vel.push(enemy.vel);
+		self.size.push(enemy.size);
+		self.health.push(enemy.health);
+		self.shoot_timer.push(enemy.shoot_timer);
+		self.firing_state.push(FiringState::default());
+		self.entities.insert()
+	}
+	pub fn swap_remove(&mut self, i: usize) {
+		self.archetype.swap_remove(i);
+		self.pos.swap_remove(i);
+		self.prev_pos.swap_remove(i);
+		self.vel.swap_remove(i);
+		self.size.swap_remove(i);
+		self.health.swap_remove(i);
+		self.shoot_timer.swap_remove(i);
+		self.firing_state.swap_remove(i);
+		self.entities.swap_remove(i);
+	}
+	// returns false if the handle is stale
+	pub fn remove(&mut self, handle: EntityHandle) -> bool {
+		let Some(i) = self.entities.get_dense_index(handle) else {return false;};
+		self.swap_remove(i);
+		true
+	}
+}
+
+
+
+// a single bullet, only used when spawning (see `Bullets` for how they're stored)
 #[derive(Debug)]
 pub struct Bullet {
 	pub pos: Vec2,
 	pub vel: Vec2,
+	pub damage: f32,
+	pub pierce_count: u32,
 }
 
 impl Bullet {
-	pub fn new(start: Vec2, dest: Vec2, speed: f32) -> Self {
+	pub fn new(start: Vec2, dest: Vec2, speed: f32, damage: f32) -> Self {
 		Self {
 			pos: start,
 			vel: (dest - start).normalize() * speed,
+			damage,
+			pierce_count: 0,
 		}
 	}
+	// `angle` is in radians, 0 is to the right
+	pub fn new_with_angle(start: Vec2, angle: f32, speed: f32, damage: f32) -> Self {
+		Self {
+			pos: start,
+			vel: Vec2::from_angle(angle) * speed,
+			damage,
+			pierce_count: 0,
+		}
+	}
+}
+
+
+
+// struct-of-arrays storage, same rules as `Enemies`
+pub struct Bullets {
+	pub entities: EntityArena,
+	pub pos: Vec<Vec2>,
+	pub prev_pos: Vec<Vec2>,
+	pub vel: Vec<Vec2>,
+	pub damage: Vec<f32>,
+	pub pierce_left: Vec<u32>,
+	// the last enemy a piercing bullet went through, so that it doesn't hit the same one again on the next tick
+	pub last_hit_enemy: Vec<Option<EntityHandle>>,
+}
+
+impl Bullets {
+	pub fn empty() -> Self {
+		Self {
+			entities: EntityArena::empty(),
+			pos: vec!(),
+			prev_pos: vec!(),
+			vel: vec!(),
+			damage: vec!(),
+			pierce_left: vec!(),
+			last_hit_enemy: vec!(),
+		}
+	}
+	pub fn len(&self) -> usize {
+		self.pos.len()
+	}
+	pub fn is_empty(&self) -> bool {
+		self.pos.is_empty()
+	}
+	pub fn push(&mut self, bullet: Bullet) -> EntityHandle {
+		self.pos.push(bullet.pos);
+		self.prev_pos.push(bullet.pos);
+		self.vel.push(bullet.vel);
+		self.damage.push(bullet.damage);
+		self.pierce_left.push(bullet.pierce_count);
+		self.last_hit_enemy.push(None);
+		self.entities.insert()
+	}
+	pub fn swap_remove(&mut self, i: usize) {
+		self.pos.swap_remove(i);
+		self.prev_pos.swap_remove(i);
+		self.vel.swap_remove(i);
+		self.damage.swap_remove(i);
+		self.pierce_left.swap_remove(i);
+		self.last_hit_enemy.swap_remove(i);
+		self.entities.swap_remove(i);
+	}
+	// returns false if the handle is stale
+	pub fn remove(&mut self, handle: EntityHandle) -> bool {
+		let Some(i) = self.entities.get_dense_index(handle) else {return false;};
+		self.swap_remove(i);
+		true
+	}
 }
 
 
@@ -0,0 +1,96 @@
+use crate::prelude::*;
+use std::fs;
+
+
+
+#[derive(Debug, Clone)]
+pub struct HighScoreEntry {
+	pub name: String,
+	pub score: usize,
+	pub date: String,
+	pub seed: u64,
+	pub duration: Duration,
+	pub difficulty: Difficulty,
+}
+
+
+
+pub fn get_high_scores_path() -> PathBuf {
+	get_program_file_path("highscores.txt")
+}
+
+
+
+// file layout: one entry per line, fields separated by tabs: name, score, date, seed, duration in seconds, difficulty
+pub fn load_high_scores(path: impl AsRef<Path>) -> Result<Vec<HighScoreEntry>> {
+	let path = path.as_ref();
+	if !path.exists() {return Ok(vec!());}
+	let file_string = fs::read_to_string(path).with_context(|| format!("Could not read high scores file {path:?}"))?;
+	
+	let mut output = vec!();
+	for (i, line) in file_string.lines().enumerate() {
+		if line.trim().is_empty() {continue;}
+		let fields = line.split('\t').collect::<Vec<_>>();
+		let [name, score, date, seed, duration, difficulty] = fields[..] else {
+			return Err(Error::msg(format!("Invalid high score entry in {path:?} line {i}: expected 6 fields, found {}", fields.len())));
+		};
+		let invalid_value = |field_name: &str| format!("Invalid {field_name} in {path:?} line {i}");
+		output.push(HighScoreEntry {
+			name: name.to_string(),
+			score: score.parse().with_context(|| invalid_value("score"))?,
+			date: date.to_string(),
+			seed: seed.parse().with_context(|| invalid_value("seed"))?,
+			// `try_from_secs_f32()` also rejects negative, infinite, and NaN durations
+			duration: Duration::try_from_secs_f32(duration.parse().with_context(|| invalid_value("duration"))?).with_context(|| invalid_value("duration"))?,
+			difficulty: Difficulty::from_name(difficulty).ok_or_else(|| Error::msg(invalid_value("difficulty")))?,
+		});
+	}
+	
+	output.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+	output.truncate(program_settings::HIGH_SCORE_COUNT);
+	Ok(output)
+}
+
+
+
+pub fn save_high_scores(path: impl AsRef<Path>, entries: &[HighScoreEntry]) -> Result<()> {
+	let path = path.as_ref();
+	let mut file_string = String::new();
+	for entry in entries {
+		file_string += &format!(
+			"{}\t{}\t{}\t{}\t{:.2}\t{}\n",
+			sanitize_high_score_name(&entry.name),
+			entry.score,
+			entry.date,
+			entry.seed,
+			entry.duration.as_secs_f32(),
+			entry.difficulty.get_name(),
+		);
+	}
+	fs::write(path, file_string).with_context(|| format!("Could not write high scores file {path:?}"))
+}
+
+
+
+pub fn sanitize_high_score_name(name: &str) -> String {
+	let name = name.replace(['\t', '\n', '\r'], " ");
+	let name = name.trim();
+	if name.is_empty() {
+		String::from("Anonymous")
+	} else {
+		name.to_string()
+	}
+}
+
+
+
+// returns where the score would be placed, or None if it doesn't make the table
+pub fn get_high_score_insert_index(entries: &[HighScoreEntry], score: usize) -> Option<usize> {
+	if score == 0 {return None;}
+	let index = entries.iter().position(|entry| score > entry.score).unwrap_or(entries.len());
+	if index < program_settings::HIGH_SCORE_COUNT {
+		Some(index)
+	} else {
+		None
+	}
+}
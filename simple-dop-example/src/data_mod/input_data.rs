@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+
+use crate::prelude::*;
+
+
+
+// plain-data copy of everything the update phases need from `App`, so they can be driven without a window
+#[derive(Debug, Clone)]
+pub struct InputSnapshot {
+	pub keys_down: HashSet<KeyCode>,
+	pub keys_pressed: HashSet<KeyCode>,
+	pub ctrl_is_down: bool,
+	pub mouse_pos: Vec2,
+	pub mouse_buttons_down: HashSet<MouseButton>,
+	pub mouse_buttons_pressed: HashSet<MouseButton>,
+	// only used for clicking on gui elements, everything else goes through the actions
+	pub left_mouse_was_pressed: bool,
+	pub screen_size: UVec2,
+	// filled in from the keys and buttons by `resolve_actions()`
+	pub actions_down: HashSet<Action>,
+	pub actions_pressed: HashSet<Action>,
+	pub gui_keyboard_data: gui::data::GuiKeyboardData,
+}
+
+impl InputSnapshot {
+	pub fn empty(screen_size: UVec2) -> Self {
+		Self {
+			keys_down: HashSet::new(),
+			keys_pressed: HashSet::new(),
+			ctrl_is_down: false,
+			mouse_pos: Vec2::default(),
+			mouse_buttons_down: HashSet::new(),
+			mouse_buttons_pressed: HashSet::new(),
+			left_mouse_was_pressed: false,
+			screen_size,
+			actions_down: HashSet::new(),
+			actions_pressed: HashSet::new(),
+			gui_keyboard_data: gui::data::GuiKeyboardData::default(),
+		}
+	}
+	pub fn key_is_down(&self, key: KeyCode) -> bool {
+		self.keys_down.contains(&key)
+	}
+	pub fn key_was_pressed(&self, key: KeyCode) -> bool {
+		self.keys_pressed.contains(&key)
+	}
+	pub fn action_is_down(&self, action: Action) -> bool {
+		self.actions_down.contains(&action)
+	}
+	pub fn action_was_pressed(&self, action: Action) -> bool {
+		self.actions_pressed.contains(&action)
+	}
+	pub fn resolve_actions(&mut self, input_bindings: &InputBindings) {
+		self.actions_down.clear();
+		self.actions_pressed.clear();
+		for action in Action::ALL {
+			let bindings = input_bindings.get(action);
+			if bindings.iter().any(|binding| binding.is_down(self)) {
+				self.actions_down.insert(action);
+			}
+			if bindings.iter().any(|binding| binding.was_pressed(self)) {
+				self.actions_pressed.insert(action);
+			}
+		}
+	}
+}
@@ -0,0 +1,179 @@
+use crate::prelude::*;
+use std::fs;
+
+
+
+// a scripted list of waves, loaded from `assets/levels/<name>.txt`
+#[derive(Debug, Clone)]
+pub struct Level {
+	pub name: String,
+	pub events: Vec<WaveEvent>,
+}
+
+impl Level {
+	// see `replay_data::get_simulation_hash()`, the name is already stored in the replay
+	pub fn write_simulation_fields(&self, hasher: &mut StableHasher) {
+		hasher.write_u64(self.events.len() as u64);
+		for event in &self.events {
+			match event {
+				WaveEvent::StartWave {name} => {
+					hasher.write_u64(0);
+					hasher.write_str(name);
+				}
+				WaveEvent::Spawn {archetype, count, position, interval_secs} => {
+					hasher.write_u64(1);
+					hasher.write_u64(*archetype as u64);
+					hasher.write_u64(*count as u64);
+					match position {
+						SpawnPosition::Random => hasher.write_u64(0),
+						SpawnPosition::Top => hasher.write_u64(1),
+						SpawnPosition::Bottom => hasher.write_u64(2),
+						SpawnPosition::Left => hasher.write_u64(3),
+						SpawnPosition::Right => hasher.write_u64(4),
+						SpawnPosition::At (pos) => {
+							hasher.write_u64(5);
+							hasher.write_f32(pos.x);
+							hasher.write_f32(pos.y);
+						}
+					}
+					hasher.write_f32(*interval_secs);
+				}
+				WaveEvent::Wait {secs} => {
+					hasher.write_u64(2);
+					hasher.write_f32(*secs);
+				}
+				WaveEvent::WaitUntilCleared => hasher.write_u64(3),
+			}
+		}
+	}
+}
+
+
+
+// the level file is run from top to bottom, one event at a time
+#[derive(Debug, Clone)]
+pub enum WaveEvent {
+	// only changes what the hud shows
+	StartWave {name: String},
+	// spawns `count` enemies, `interval_secs` apart, and only moves on once all of them are spawned
+	Spawn {archetype: usize, count: usize, position: SpawnPosition, interval_secs: f32},
+	Wait {secs: f32},
+	WaitUntilCleared,
+}
+
+
+
+#[derive(Debug, Clone, Copy)]
+pub enum SpawnPosition {
+	Random,
+	Top,
+	Bottom,
+	Left,
+	Right,
+	At (Vec2),
+}
+
+
+
+// progress through `PlayingData::level`
+#[derive(Debug)]
+pub struct WaveState {
+	pub next_event_index: usize,
+	pub event_started: bool,
+	pub event_timer: f32,
+	pub spawns_left: usize,
+	pub wave_number: usize,
+	pub wave_name: String,
+	// all events are done and all enemies are dead
+	pub is_finished: bool,
+}
+
+impl WaveState {
+	pub fn new() -> Self {
+		Self {
+			next_event_index: 0,
+			event_started: false,
+			event_timer: 0.,
+			spawns_left: 0,
+			wave_number: 0,
+			wave_name: String::new(),
+			is_finished: false,
+		}
+	}
+}
+
+
+
+pub fn get_level_path(name: &str) -> PathBuf {
+	get_program_file_path(format!("assets/levels/{name}.txt"))
+}
+
+
+
+// layout: one `key: value` per line, `//` starts a comment, and unlike the other files the order of the lines matters
+// wave: <name>
+// spawn interval: <seconds>  (applies to every `spawn` after it, defaults to 0)
+// spawn: <archetype> <count> <random | top | bottom | left | right | x y>
+// wait: <seconds | cleared>
+pub fn load_level(name: &str, enemy_archetypes: &[EnemyArchetype]) -> Result<Level> {
+	// replays store the level name's length in one byte
+	if name.len() > u8::MAX as usize {
+		return Err(Error::msg(format!("Level name \"{name}\" is too long (max {} bytes)", u8::MAX)));
+	}
+	let path = &get_level_path(name);
+	let file_string = fs::read_to_string(path).with_context(|| format!("Could not read level file {path:?}"))?;
+	let mut events = vec!();
+	let mut spawn_interval = 0.;
+	
+	for line in gui::load::parse_key_value_lines(&file_string, path) {
+		let (i, field_name, field_value) = line?;
+		
+		match field_name {
+			"wave" => events.push(WaveEvent::StartWave {name: field_value.to_string()}),
+			"spawn interval" => spawn_interval = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"spawn" => events.push(parse_spawn_event(field_value, spawn_interval, enemy_archetypes, i, path)?),
+			"wait" => {
+				if field_value == "cleared" {
+					events.push(WaveEvent::WaitUntilCleared);
+				} else {
+					let secs = gui::load::parse_value_to_f32(field_value, i, path)?;
+					events.push(WaveEvent::Wait {secs});
+				}
+			}
+			_ => return Err(Error::msg(format!("Invalid level field \"{field_name}\" in {path:?} line {i}"))),
+		}
+		
+	}
+	
+	Ok(Level {
+		name: name.to_string(),
+		events,
+	})
+}
+
+
+
+pub fn parse_spawn_event(value: &str, interval_secs: f32, enemy_archetypes: &[EnemyArchetype], line: usize, path: &Path) -> Result<WaveEvent> {
+	let invalid_spawn = || Error::msg(format!("Invalid spawn \"{value}\" in {path:?} line {line} (expected `<archetype> <count> <position>`)"));
+	let parts = value.split_whitespace().collect::<Vec<_>>();
+	let [archetype_name, count, position @ ..] = &parts[..] else {return Err(invalid_spawn());};
+	
+	let archetype = get_enemy_archetype_index(enemy_archetypes, archetype_name)
+		.ok_or_else(|| Error::msg(format!("Unknown enemy archetype \"{archetype_name}\" in {path:?} line {line}")))?;
+	let count = count.parse().map_err(|_| invalid_spawn())?;
+	let position = match position {
+		[] | ["random"] => SpawnPosition::Random,
+		["top"] => SpawnPosition::Top,
+		["bottom"] => SpawnPosition::Bottom,
+		["left"] => SpawnPosition::Left,
+		["right"] => SpawnPosition::Right,
+		[x, y] => {
+			let x = gui::load::parse_value_to_f32(x, line, path)?;
+			let y = gui::load::parse_value_to_f32(y, line, path)?;
+			SpawnPosition::At (Vec2::new(x, y))
+		}
+		_ => return Err(invalid_spawn()),
+	};
+	
+	Ok(WaveEvent::Spawn {archetype, count, position, interval_secs})
+}
@@ -1 +1,15 @@
 pub mod general_data;
+pub mod input_data;
+pub mod replay_data;
+pub mod high_score_data;
+pub mod spatial_hash;
+pub mod entity_data;
+pub mod enemy_data;
+pub mod level_data;
+pub mod pickup_data;
+pub mod weapon_data;
+pub mod settings_data;
+pub mod difficulty_data;
+pub mod action_data;
+pub mod particle_data;
+pub mod camera_data;
@@ -0,0 +1,268 @@
+use crate::prelude::*;
+use notan::random::rand::Rng;
+use std::{fs, collections::HashMap};
+
+
+
+// every gameplay event that gives off particles, each one has a preset in `assets/particles`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParticleEffect {
+	EnemyHit,
+	EnemyDeath,
+	PlayerHit,
+	MuzzleFlash,
+}
+
+impl ParticleEffect {
+	pub const ALL: [Self; 4] = [Self::EnemyHit, Self::EnemyDeath, Self::PlayerHit, Self::MuzzleFlash];
+	pub fn get_file_name(&self) -> &'static str {
+		match self {
+			Self::EnemyHit    => "enemy_hit",
+			Self::EnemyDeath  => "enemy_death",
+			Self::PlayerHit   => "player_hit",
+			Self::MuzzleFlash => "muzzle_flash",
+		}
+	}
+}
+
+
+
+// how a particle goes from `start_size` to `end_size` over its lifetime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeCurve {
+	Linear,
+	// changes slowly at first, then quickly
+	EaseIn,
+	// changes quickly at first, then slowly
+	EaseOut,
+}
+
+impl SizeCurve {
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"linear"   => Some(Self::Linear),
+			"ease in"  => Some(Self::EaseIn),
+			"ease out" => Some(Self::EaseOut),
+			_ => None,
+		}
+	}
+	// `t` is how far through its lifetime the particle is (0 to 1)
+	pub fn apply(&self, t: f32) -> f32 {
+		match self {
+			Self::Linear => t,
+			Self::EaseIn => t * t,
+			Self::EaseOut => 1. - (1. - t) * (1. - t),
+		}
+	}
+}
+
+
+
+// one burst of particles, loaded from `assets/particles/<name>.txt`
+#[derive(Debug, Clone)]
+pub struct ParticleEmitter {
+	pub name: String,
+	pub count: usize,
+	pub min_speed: f32,
+	pub max_speed: f32,
+	// the particles are sent out within this angle around the emit direction, 360 sends them everywhere
+	pub spread_angle_degrees: f32,
+	pub min_lifetime_secs: f32,
+	pub max_lifetime_secs: f32,
+	// fraction of the velocity that's left after one second
+	pub drag_coef: f32,
+	pub start_color: Color,
+	pub end_color: Color,
+	pub start_size: f32,
+	pub end_size: f32,
+	pub size_curve: SizeCurve,
+}
+
+impl ParticleEmitter {
+	// values for any keys that the file leaves out
+	pub fn new(name: impl Into<String>) -> Self {
+		Self {
+			name: name.into(),
+			count: 8,
+			min_speed: 0.1,
+			max_speed: 0.3,
+			spread_angle_degrees: 360.,
+			min_lifetime_secs: 0.2,
+			max_lifetime_secs: 0.4,
+			drag_coef: 0.05,
+			start_color: Color::WHITE,
+			end_color: Color::new(1., 1., 1., 0.),
+			start_size: 0.01,
+			end_size: 0.,
+			size_curve: SizeCurve::Linear,
+		}
+	}
+	// `t` is how far through its lifetime the particle is (0 to 1)
+	pub fn get_color(&self, t: f32) -> Color {
+		let (start, end) = (self.start_color, self.end_color);
+		Color::new(
+			start.r + (end.r - start.r) * t,
+			start.g + (end.g - start.g) * t,
+			start.b + (end.b - start.b) * t,
+			start.a + (end.a - start.a) * t,
+		)
+	}
+	pub fn get_size(&self, t: f32) -> f32 {
+		self.start_size + (self.end_size - self.start_size) * self.size_curve.apply(t)
+	}
+}
+
+
+
+pub fn get_particle_emitters_path() -> PathBuf {
+	get_program_file_path("assets/particles")
+}
+
+
+
+// every effect in `ParticleEffect::ALL` needs a file, extra files are ignored
+pub fn load_particle_emitters(folder: impl AsRef<Path>) -> Result<HashMap<ParticleEffect, ParticleEmitter>> {
+	let folder = folder.as_ref();
+	let mut output = HashMap::new();
+	let mut errors = vec!();
+	for effect in ParticleEffect::ALL {
+		let path = folder.join(format!("{}.txt", effect.get_file_name()));
+		match load_particle_emitter(&path) {
+			Result::Ok (emitter) => {output.insert(effect, emitter);}
+			Result::Err (err) => errors.push(err),
+		}
+	}
+	if !errors.is_empty() {
+		return Err(PackagedErrors::new(errors).into());
+	}
+	Ok(output)
+}
+
+
+
+// same layout as the gui files: one `key: value` per line, `//` starts a comment
+pub fn load_particle_emitter(path: &Path) -> Result<ParticleEmitter> {
+	let name = gui::load::get_file_name(path)?;
+	let mut output = ParticleEmitter::new(name);
+	
+	let file_string = fs::read_to_string(path).with_context(|| format!("Could not read particle emitter file {path:?}"))?;
+	for line in gui::load::parse_key_value_lines(&file_string, path) {
+		let (i, field_name, field_value) = line?;
+		
+		match field_name {
+			"count"        => output.count                = field_value.parse().with_context(|| format!("Invalid count \"{field_value}\" in {path:?} line {i}"))?,
+			"min speed"    => output.min_speed            = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"max speed"    => output.max_speed            = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"spread angle" => output.spread_angle_degrees = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"min lifetime" => output.min_lifetime_secs    = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"max lifetime" => output.max_lifetime_secs    = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"drag"         => output.drag_coef            = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"start color"  => output.start_color          = gui::load::parse_value_to_color(field_value, i, path)?.as_notan_color(),
+			"end color"    => output.end_color            = gui::load::parse_value_to_color(field_value, i, path)?.as_notan_color(),
+			"start size"   => output.start_size           = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"end size"     => output.end_size             = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"size curve"   => output.size_curve           = SizeCurve::from_name(field_value).with_context(|| format!("Invalid size curve \"{field_value}\" in {path:?} line {i}"))?,
+			_ => return Err(Error::msg(format!("Invalid particle emitter field \"{field_name}\" in {path:?} line {i}"))),
+		}
+		
+	}
+	
+	if output.min_lifetime_secs <= 0. || output.max_lifetime_secs < output.min_lifetime_secs {
+		return Err(Error::msg(format!("Particle emitter {path:?} must have a min lifetime above 0 and a max lifetime of at least the min lifetime")));
+	}
+	if output.max_speed < output.min_speed {
+		return Err(Error::msg(format!("Particle emitter {path:?} must have a max speed of at least the min speed")));
+	}
+	if !(0. ..=1.).contains(&output.drag_coef) {
+		return Err(Error::msg(format!("Particle emitter {path:?} must have a drag between 0 and 1")));
+	}
+	Ok(output)
+}
+
+
+
+// struct-of-arrays pool, the vecs are allocated once up front and never grow past `capacity`
+// particles are purely visual so there are no entity handles, they're only ever removed by `update()`
+#[derive(Debug)]
+pub struct Particles {
+	pub capacity: usize,
+	pub effect: Vec<ParticleEffect>,
+	pub pos: Vec<Vec2>,
+	pub prev_pos: Vec<Vec2>,
+	pub vel: Vec<Vec2>,
+	pub age_secs: Vec<f32>,
+	pub lifetime_secs: Vec<f32>,
+}
+
+impl Particles {
+	pub fn with_capacity(capacity: usize) -> Self {
+		Self {
+			capacity,
+			effect: Vec::with_capacity(capacity),
+			pos: Vec::with_capacity(capacity),
+			prev_pos: Vec::with_capacity(capacity),
+			vel: Vec::with_capacity(capacity),
+			age_secs: Vec::with_capacity(capacity),
+			lifetime_secs: Vec::with_capacity(capacity),
+		}
+	}
+	pub fn len(&self) -> usize {
+		self.pos.len()
+	}
+	pub fn is_empty(&self) -> bool {
+		self.pos.is_empty()
+	}
+	// keeps the allocations so that the next run doesn't have to make them again
+	pub fn clear(&mut self) {
+		self.effect.clear();
+		self.pos.clear();
+		self.prev_pos.clear();
+		self.vel.clear();
+		self.age_secs.clear();
+		self.lifetime_secs.clear();
+	}
+	// new particles are dropped once the pool is full
+	pub fn push(&mut self, effect: ParticleEffect, pos: Vec2, vel: Vec2, lifetime_secs: f32) {
+		if self.len() >= self.capacity {return;}
+		self.effect.push(effect);
+		self.pos.push(pos);
+		self.prev_pos.push(pos);
+		self.vel.push(vel);
+		self.age_secs.push(0.);
+		self.lifetime_secs.push(lifetime_secs);
+	}
+	// the last particle is moved into index `i`
+	pub fn swap_remove(&mut self, i: usize) {
+		self.effect.swap_remove(i);
+		self.pos.swap_remove(i);
+		self.prev_pos.swap_remove(i);
+		self.vel.swap_remove(i);
+		self.age_secs.swap_remove(i);
+		self.lifetime_secs.swap_remove(i);
+	}
+	// sends out a burst from `effect`'s emitter, centered on `dir` (a zero `dir` picks a random direction)
+	pub fn emit(&mut self, emitters: &HashMap<ParticleEffect, ParticleEmitter>, effect: ParticleEffect, pos: Vec2, dir: Vec2, rng: &mut impl Rng) {
+		let Some(emitter) = emitters.get(&effect) else {return;};
+		let base_angle = if dir == Vec2::ZERO {rng.gen::<f32>() * std::f32::consts::TAU} else {dir.y.atan2(dir.x)};
+		let spread = emitter.spread_angle_degrees.to_radians();
+		for _ in 0..emitter.count {
+			let angle = base_angle + (rng.gen::<f32>() - 0.5) * spread;
+			let speed = rng.gen_range(emitter.min_speed..=emitter.max_speed);
+			let lifetime_secs = rng.gen_range(emitter.min_lifetime_secs..=emitter.max_lifetime_secs);
+			self.push(effect, pos, Vec2::from_angle(angle) * speed, lifetime_secs);
+		}
+	}
+	pub fn update(&mut self, emitters: &HashMap<ParticleEffect, ParticleEmitter>, dt: f32) {
+		self.prev_pos.copy_from_slice(&self.pos);
+		for i in (0..self.len()).rev() {
+			self.age_secs[i] += dt;
+			if self.age_secs[i] >= self.lifetime_secs[i] {
+				self.swap_remove(i);
+				continue;
+			}
+			self.pos[i] += self.vel[i] * dt;
+			let drag_coef = emitters.get(&self.effect[i]).map(|emitter| emitter.drag_coef).unwrap_or(1.);
+			self.vel[i] *= drag_coef.powf(dt);
+		}
+	}
+}
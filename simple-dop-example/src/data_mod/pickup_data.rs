@@ -0,0 +1,124 @@
+use crate::prelude::*;
+use notan::random::rand::Rng;
+
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PickupKind {
+	Health,
+	RapidFire,
+	SpreadShot,
+	Shield,
+	ScoreBonus,
+	WeaponUpgrade,
+}
+
+impl PickupKind {
+	pub const ALL: [Self; 6] = [Self::Health, Self::RapidFire, Self::SpreadShot, Self::Shield, Self::ScoreBonus, Self::WeaponUpgrade];
+	pub fn get_texture_name(&self) -> &'static str {
+		match self {
+			Self::Health     => "pickup_health",
+			Self::RapidFire  => "pickup_rapid_fire",
+			Self::SpreadShot => "pickup_spread_shot",
+			Self::Shield     => "pickup_shield",
+			Self::ScoreBonus => "pickup_score_bonus",
+			Self::WeaponUpgrade => "pickup_weapon_upgrade",
+		}
+	}
+}
+
+
+
+// a single pickup, only used when spawning (see `Pickups` for how they're stored)
+#[derive(Debug)]
+pub struct Pickup {
+	pub kind: PickupKind,
+	pub pos: Vec2,
+	pub vel: Vec2,
+	pub secs_left: f32,
+}
+
+impl Pickup {
+	pub fn new(kind: PickupKind, pos: Vec2, settings: &ProgramSettings, rng: &mut impl Rng) -> Self {
+		let angle = rng.gen::<f32>() * std::f32::consts::TAU;
+		Self {
+			kind,
+			pos,
+			vel: Vec2::from_angle(angle) * settings.pickup_speed,
+			secs_left: settings.pickup_lifetime_secs,
+		}
+	}
+}
+
+
+
+// struct-of-arrays storage, every column always has the same length and index `i` in each column is the same pickup
+// nothing refers to a pickup across ticks so there are no entity handles, they're only ever removed by `update_pickups()`
+#[derive(Debug)]
+pub struct Pickups {
+	pub kind: Vec<PickupKind>,
+	pub pos: Vec<Vec2>,
+	pub prev_pos: Vec<Vec2>,
+	pub vel: Vec<Vec2>,
+	pub secs_left: Vec<f32>,
+}
+
+impl Pickups {
+	pub fn empty() -> Self {
+		Self {
+			kind: vec!(),
+			pos: vec!(),
+			prev_pos: vec!(),
+			vel: vec!(),
+			secs_left: vec!(),
+		}
+	}
+	pub fn len(&self) -> usize {
+		self.pos.len()
+	}
+	pub fn is_empty(&self) -> bool {
+		self.pos.is_empty()
+	}
+	pub fn push(&mut self, pickup: Pickup) {
+		self.kind.push(pickup.kind);
+		self.pos.push(pickup.pos);
+		self.prev_pos.push(pickup.pos);
+		self.vel.push(pickup.vel);
+		self.secs_left.push(pickup.secs_left);
+	}
+	// the last pickup is moved into index `i`
+	pub fn swap_remove(&mut self, i: usize) {
+		self.kind.swap_remove(i);
+		self.pos.swap_remove(i);
+		self.prev_pos.swap_remove(i);
+		self.vel.swap_remove(i);
+		self.secs_left.swap_remove(i);
+	}
+}
+
+
+
+// seconds left on each timed pickup effect, 0 means inactive
+#[derive(Debug, Clone, Default)]
+pub struct ActiveEffects {
+	pub rapid_fire_secs: f32,
+	pub spread_shot_secs: f32,
+	pub shield_secs: f32,
+}
+
+impl ActiveEffects {
+	pub fn tick(&mut self, dt: f32) {
+		self.rapid_fire_secs = (self.rapid_fire_secs - dt).max(0.);
+		self.spread_shot_secs = (self.spread_shot_secs - dt).max(0.);
+		self.shield_secs = (self.shield_secs - dt).max(0.);
+	}
+	pub fn has_rapid_fire(&self) -> bool {
+		self.rapid_fire_secs > 0.
+	}
+	pub fn has_spread_shot(&self) -> bool {
+		self.spread_shot_secs > 0.
+	}
+	pub fn has_shield(&self) -> bool {
+		self.shield_secs > 0.
+	}
+}
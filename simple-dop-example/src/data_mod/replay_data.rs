@@ -0,0 +1,243 @@
+use crate::prelude::*;
+use std::{fs, io::Write};
+
+
+
+// everything the player can do that affects a single tick of `update_game()`
+#[derive(Debug, Clone, Default)]
+pub struct TickInput {
+	pub move_up: bool,
+	pub move_down: bool,
+	pub move_left: bool,
+	pub move_right: bool,
+	pub fire_held: bool,
+	// in arena coordinates (0 to 1 across the arena, so it doesn't depend on the window or camera), only meaningful while `fire_held` is set
+	pub aim_target: Vec2,
+	// set for the tick that a weapon switch key was pressed
+	pub weapon_slot: Option<u8>,
+	pub pause_toggled: bool,
+}
+
+impl TickInput {
+	pub fn get_move_dir(&self) -> Vec2 {
+		let mut output = Vec2::new(0., 0.);
+		if self.move_up    {output.y -= 1.;}
+		if self.move_down  {output.y += 1.;}
+		if self.move_left  {output.x -= 1.;}
+		if self.move_right {output.x += 1.;}
+		output
+	}
+}
+
+
+
+pub struct Replay {
+	pub seed: u64,
+	pub level_name: Option<String>,
+	pub difficulty: Difficulty,
+	// from `get_simulation_hash()`, playback is refused when the currently loaded files don't match
+	pub simulation_hash: u64,
+	// only used (and only saved) when `difficulty` is custom, since the simulation hash leaves these out
+	pub custom_difficulty: DifficultyModifiers,
+	pub tick_inputs: Vec<TickInput>,
+}
+
+
+
+// file layout (all little endian):
+// "DOPR", version: u8, ticks per second: u32, seed: u64, level name length: u8 (0 for endless), level name: utf8, difficulty id: u8, simulation hash: u64,
+// then if the difficulty is custom: (enemy speed, enemy fire rate, enemy spawn acceleration, enemy bullet speed, player health): f32 coefs, then tick count: u32
+// then for every tick: flags: u8, then if FLAG_FIRE_HELD is set: (aim x: f32, aim y: f32), then if FLAG_WEAPON_SWITCH is set: slot: u8
+// nothing can come after the last tick
+pub const REPLAY_MAGIC: &[u8; 4] = b"DOPR";
+pub const REPLAY_VERSION: u8 = 1;
+// the oldest version that can still be played back
+pub const MIN_REPLAY_VERSION: u8 = 1;
+
+const FLAG_MOVE_UP: u8 = 1 << 0;
+const FLAG_MOVE_DOWN: u8 = 1 << 1;
+const FLAG_MOVE_LEFT: u8 = 1 << 2;
+const FLAG_MOVE_RIGHT: u8 = 1 << 3;
+const FLAG_PAUSE_TOGGLED: u8 = 1 << 4;
+const FLAG_FIRE_HELD: u8 = 1 << 5;
+const FLAG_WEAPON_SWITCH: u8 = 1 << 6;
+
+impl Replay {
+	
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut output = vec!();
+		output.extend_from_slice(REPLAY_MAGIC);
+		output.push(REPLAY_VERSION);
+		output.extend_from_slice(&program_settings::TICKS_PER_SECOND.to_le_bytes());
+		output.extend_from_slice(&self.seed.to_le_bytes());
+		let level_name = self.level_name.as_deref().unwrap_or("").as_bytes();
+		output.push(level_name.len() as u8);
+		output.extend_from_slice(level_name);
+		output.push(self.difficulty.to_id());
+		output.extend_from_slice(&self.simulation_hash.to_le_bytes());
+		if self.difficulty == Difficulty::Custom {
+			let modifiers = &self.custom_difficulty;
+			for coef in [modifiers.enemy_speed_coef, modifiers.enemy_fire_rate_coef, modifiers.enemy_spawn_acceleration_coef, modifiers.enemy_bullet_speed_coef, modifiers.player_health_coef] {
+				output.extend_from_slice(&coef.to_le_bytes());
+			}
+		}
+		output.extend_from_slice(&(self.tick_inputs.len() as u32).to_le_bytes());
+		for tick_input in &self.tick_inputs {
+			let mut flags = 0;
+			if tick_input.move_up    {flags |= FLAG_MOVE_UP;}
+			if tick_input.move_down  {flags |= FLAG_MOVE_DOWN;}
+			if tick_input.move_left  {flags |= FLAG_MOVE_LEFT;}
+			if tick_input.move_right {flags |= FLAG_MOVE_RIGHT;}
+			if tick_input.pause_toggled {flags |= FLAG_PAUSE_TOGGLED;}
+			if tick_input.fire_held {flags |= FLAG_FIRE_HELD;}
+			if tick_input.weapon_slot.is_some() {flags |= FLAG_WEAPON_SWITCH;}
+			output.push(flags);
+			if tick_input.fire_held {
+				output.extend_from_slice(&tick_input.aim_target.x.to_le_bytes());
+				output.extend_from_slice(&tick_input.aim_target.y.to_le_bytes());
+			}
+			if let Some(slot) = tick_input.weapon_slot {
+				output.push(slot);
+			}
+		}
+		output
+	}
+	
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+		let mut index = 0;
+		if read_bytes::<4>(bytes, &mut index)? != *REPLAY_MAGIC {
+			return Err(Error::msg("Not a replay file (invalid header)"));
+		}
+		let version = read_bytes::<1>(bytes, &mut index)?[0];
+		if !(MIN_REPLAY_VERSION..=REPLAY_VERSION).contains(&version) {
+			return Err(Error::msg(format!("Unsupported replay version: {version} (expected {MIN_REPLAY_VERSION} to {REPLAY_VERSION})")));
+		}
+		let ticks_per_second = u32::from_le_bytes(read_bytes(bytes, &mut index)?);
+		if ticks_per_second != program_settings::TICKS_PER_SECOND {
+			return Err(Error::msg(format!("Replay was recorded at {ticks_per_second} ticks per second, but the game runs at {}", program_settings::TICKS_PER_SECOND)));
+		}
+		let seed = u64::from_le_bytes(read_bytes(bytes, &mut index)?);
+		let mut level_name = None;
+		let level_name_len = read_bytes::<1>(bytes, &mut index)?[0] as usize;
+		let level_name_bytes = bytes.get(index .. index + level_name_len).ok_or_else(|| Error::msg("Replay file ended unexpectedly (in level name)"))?;
+		index += level_name_len;
+		if level_name_len > 0 {
+			level_name = Some(String::from_utf8(level_name_bytes.to_vec()).context("Invalid level name in replay file")?);
+		}
+		let difficulty_id = read_bytes::<1>(bytes, &mut index)?[0];
+		let difficulty = Difficulty::from_id(difficulty_id).ok_or_else(|| Error::msg(format!("Invalid difficulty id in replay file: {difficulty_id}")))?;
+		let simulation_hash = u64::from_le_bytes(read_bytes(bytes, &mut index)?);
+		let mut custom_difficulty = DifficultyModifiers::new();
+		if difficulty == Difficulty::Custom {
+			custom_difficulty.enemy_speed_coef = f32::from_le_bytes(read_bytes(bytes, &mut index)?);
+			custom_difficulty.enemy_fire_rate_coef = f32::from_le_bytes(read_bytes(bytes, &mut index)?);
+			custom_difficulty.enemy_spawn_acceleration_coef = f32::from_le_bytes(read_bytes(bytes, &mut index)?);
+			custom_difficulty.enemy_bullet_speed_coef = f32::from_le_bytes(read_bytes(bytes, &mut index)?);
+			custom_difficulty.player_health_coef = f32::from_le_bytes(read_bytes(bytes, &mut index)?);
+		}
+		let tick_count = u32::from_le_bytes(read_bytes(bytes, &mut index)?);
+		
+		// every tick takes at least one byte, so a corrupt tick count can't make this allocate more than the file size
+		let mut tick_inputs = Vec::with_capacity((tick_count as usize).min(bytes.len()));
+		for _ in 0..tick_count {
+			let flags = read_bytes::<1>(bytes, &mut index)?[0];
+			let mut tick_input = TickInput {
+				move_up: flags & FLAG_MOVE_UP != 0,
+				move_down: flags & FLAG_MOVE_DOWN != 0,
+				move_left: flags & FLAG_MOVE_LEFT != 0,
+				move_right: flags & FLAG_MOVE_RIGHT != 0,
+				fire_held: flags & FLAG_FIRE_HELD != 0,
+				aim_target: Vec2::ZERO,
+				weapon_slot: None,
+				pause_toggled: flags & FLAG_PAUSE_TOGGLED != 0,
+			};
+			if tick_input.fire_held {
+				let x = f32::from_le_bytes(read_bytes(bytes, &mut index)?);
+				let y = f32::from_le_bytes(read_bytes(bytes, &mut index)?);
+				tick_input.aim_target = Vec2::new(x, y);
+			}
+			if flags & FLAG_WEAPON_SWITCH != 0 {
+				tick_input.weapon_slot = Some(read_bytes::<1>(bytes, &mut index)?[0]);
+			}
+			tick_inputs.push(tick_input);
+		}
+		if index != bytes.len() {
+			return Err(Error::msg(format!("Replay file has {} unexpected bytes after the last tick", bytes.len() - index)));
+		}
+		
+		Ok(Self {
+			seed,
+			level_name,
+			difficulty,
+			simulation_hash,
+			custom_difficulty,
+			tick_inputs,
+		})
+	}
+	
+}
+
+fn read_bytes<const N: usize>(bytes: &[u8], index: &mut usize) -> Result<[u8; N]> {
+	let output = bytes
+		.get(*index .. *index + N)
+		.ok_or_else(|| Error::msg(format!("Replay file ended unexpectedly (at byte {})", *index)))?;
+	*index += N;
+	Ok(output.try_into().expect("slice should have length N"))
+}
+
+
+
+// covers everything loaded from files that changes what happens in a run, so that editing any of it can't make a replay quietly play back differently
+pub fn get_simulation_hash(settings: &ProgramSettings, enemy_archetypes: &[EnemyArchetype], weapons: &[WeaponDefinition], level: Option<&Level>) -> u64 {
+	let mut hasher = StableHasher::new();
+	settings.write_simulation_fields(&mut hasher);
+	hasher.write_u64(enemy_archetypes.len() as u64);
+	for archetype in enemy_archetypes {
+		archetype.write_simulation_fields(&mut hasher);
+	}
+	hasher.write_u64(weapons.len() as u64);
+	for weapon in weapons {
+		weapon.write_simulation_fields(&mut hasher);
+	}
+	match level {
+		Some(level) => {
+			hasher.write_u64(1);
+			level.write_simulation_fields(&mut hasher);
+		}
+		None => hasher.write_u64(0),
+	}
+	hasher.finish()
+}
+
+
+
+pub fn save_replay(replay: &Replay) -> Result<PathBuf> {
+	// `load_level()` already rejects these, but a truncated length would make the whole file unreadable
+	let level_name_len = replay.level_name.as_deref().map(str::len).unwrap_or(0);
+	if level_name_len > u8::MAX as usize {
+		return Err(Error::msg(format!("Could not save replay, the level name is too long ({level_name_len} bytes, max {})", u8::MAX)));
+	}
+	let mut path = get_program_dir();
+	path.push("replays");
+	fs::create_dir_all(&path).with_context(|| format!("Could not create replay folder {path:?}"))?;
+	let timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
+	// several replays can be saved in the same second, so a counter is added instead of overwriting the earlier ones
+	let mut i = 0;
+	let (mut file, path) = loop {
+		let file_name = if i == 0 {format!("replay_{timestamp}.replay")} else {format!("replay_{timestamp}_{i}.replay")};
+		let file_path = path.join(file_name);
+		match fs::OpenOptions::new().write(true).create_new(true).open(&file_path) {
+			Result::Ok (file) => break (file, file_path),
+			Result::Err (err) if err.kind() == std::io::ErrorKind::AlreadyExists => i += 1,
+			Result::Err (err) => return Err(Error::new(err).context(format!("Could not create replay file {file_path:?}"))),
+		}
+	};
+	file.write_all(&replay.to_bytes()).with_context(|| format!("Could not write replay file {path:?}"))?;
+	Ok(path)
+}
+
+pub fn load_replay(path: impl AsRef<Path>) -> Result<Replay> {
+	let path = path.as_ref();
+	let bytes = fs::read(path).with_context(|| format!("Could not read replay file {path:?}"))?;
+	Replay::from_bytes(&bytes).with_context(|| format!("Could not load replay file {path:?}"))
+}
@@ -0,0 +1,310 @@
+use crate::prelude::*;
+use std::fs;
+
+
+
+// gameplay tuning, loaded from `assets/settings.txt` at init and reloaded with ctrl+r (reloads apply to the next run)
+// most of these change the simulation, so they're part of the hash that replays use to refuse playing back with different data
+// (things that can't change at all without breaking saved data, like the tick rate, stay in `program_settings`)
+#[derive(Debug, Clone)]
+pub struct ProgramSettings {
+	
+	pub player_acceleration: f32,
+	pub player_drag_coef: f32,
+	pub player_size: f32,
+	
+	pub player_max_health: f32,
+	pub player_invincibility_secs: f32,
+	pub player_flash_interval_secs: f32,
+	pub player_regen_delay_secs: f32,
+	pub player_regen_per_sec: f32,
+	// speed the player is thrown away from an enemy they touch
+	pub player_knockback_speed: f32,
+	
+	// the weapon every run starts with, the rest are unlocked with weapon upgrade pickups
+	pub starting_weapon: String,
+	pub bullet_size: f32,
+	
+	pub pickup_drop_chance: f32,
+	pub pickup_size: f32,
+	pub pickup_speed: f32,
+	pub pickup_lifetime_secs: f32,
+	// pickups blink for this long before they disappear
+	pub pickup_blink_secs: f32,
+	pub pickup_heal_amount: f32,
+	pub pickup_score_bonus: usize,
+	pub power_up_duration_secs: f32,
+	pub rapid_fire_wait_coef: f32,
+	pub spread_shot_angle_degrees: f32,
+	
+	pub enemy_spawn_wait_secs: f32,
+	pub enemy_spawn_wait_coef_percent: u32,
+	// endless mode (used when no level is selected) picks from these archetype names at random
+	pub endless_enemy_archetypes: Vec<String>,
+	
+	pub combo_multiplier_step: f32,
+	pub combo_multiplier_max: f32,
+	pub combo_decay_delay_secs: f32,
+	pub combo_decay_per_sec: f32,
+	pub survival_score_interval_secs: f32,
+	pub survival_score_per_interval: usize,
+	
+	// the multipliers used by `Difficulty::Custom`
+	pub custom_difficulty: DifficultyModifiers,
+	
+	pub camera_fit_mode: CameraFitMode,
+	// 1 places the arena as `camera_fit_mode` says, above 1 zooms in, and the view follows the player on any axis that doesn't fit
+	pub camera_zoom: f32,
+	// how far the view moves (in arena units) at full trauma
+	pub camera_shake_max_offset: f32,
+	pub camera_shake_frequency: f32,
+	pub camera_trauma_decay_per_sec: f32,
+	pub camera_trauma_per_kill: f32,
+	pub camera_trauma_per_player_hit: f32,
+	
+	// width and height of the crosshair drawn where the player is aiming
+	pub crosshair_size: f32,
+	
+}
+
+impl ProgramSettings {
+	// values for any keys that the file leaves out
+	pub fn new() -> Self {
+		Self {
+			
+			player_acceleration: 1.7,
+			player_drag_coef: 0.01,
+			player_size: 0.05,
+			
+			player_max_health: 1.,
+			player_invincibility_secs: 1.,
+			player_flash_interval_secs: 0.08,
+			player_regen_delay_secs: 4.,
+			player_regen_per_sec: 0.04,
+			player_knockback_speed: 0.9,
+			
+			starting_weapon: String::from("single"),
+			bullet_size: 0.015,
+			
+			pickup_drop_chance: 0.12,
+			pickup_size: 0.035,
+			pickup_speed: 0.04,
+			pickup_lifetime_secs: 10.,
+			pickup_blink_secs: 2.,
+			pickup_heal_amount: 0.35,
+			pickup_score_bonus: 500,
+			power_up_duration_secs: 8.,
+			rapid_fire_wait_coef: 0.5,
+			spread_shot_angle_degrees: 15.,
+			
+			enemy_spawn_wait_secs: 1.,
+			enemy_spawn_wait_coef_percent: 98,
+			endless_enemy_archetypes: ["basic", "basic", "basic", "chaser", "chaser", "sniper", "gunner", "turret", "spinner", "bomber"].map(String::from).to_vec(),
+			
+			combo_multiplier_step: 0.25,
+			combo_multiplier_max: 5.,
+			combo_decay_delay_secs: 1.5,
+			combo_decay_per_sec: 0.5,
+			survival_score_interval_secs: 1.,
+			survival_score_per_interval: 10,
+			
+			custom_difficulty: DifficultyModifiers::new(),
+			
+			camera_fit_mode: CameraFitMode::Fit,
+			camera_zoom: 1.,
+			camera_shake_max_offset: 0.02,
+			camera_shake_frequency: 40.,
+			camera_trauma_decay_per_sec: 1.5,
+			camera_trauma_per_kill: 0.15,
+			camera_trauma_per_player_hit: 0.6,
+			
+			crosshair_size: 0.03,
+			
+		}
+	}
+	// covers every field that changes what happens in a run, new fields need to be added here
+	// the blink timings, camera and crosshair only change what's drawn, and the custom difficulty is stored in the replay itself
+	pub fn write_simulation_fields(&self, hasher: &mut StableHasher) {
+		
+		hasher.write_f32(self.player_acceleration);
+		hasher.write_f32(self.player_drag_coef);
+		hasher.write_f32(self.player_size);
+		
+		hasher.write_f32(self.player_max_health);
+		hasher.write_f32(self.player_invincibility_secs);
+		hasher.write_f32(self.player_regen_delay_secs);
+		hasher.write_f32(self.player_regen_per_sec);
+		hasher.write_f32(self.player_knockback_speed);
+		
+		hasher.write_str(&self.starting_weapon);
+		hasher.write_f32(self.bullet_size);
+		
+		hasher.write_f32(self.pickup_drop_chance);
+		hasher.write_f32(self.pickup_size);
+		hasher.write_f32(self.pickup_speed);
+		hasher.write_f32(self.pickup_lifetime_secs);
+		hasher.write_f32(self.pickup_heal_amount);
+		hasher.write_u64(self.pickup_score_bonus as u64);
+		hasher.write_f32(self.power_up_duration_secs);
+		hasher.write_f32(self.rapid_fire_wait_coef);
+		hasher.write_f32(self.spread_shot_angle_degrees);
+		
+		hasher.write_f32(self.enemy_spawn_wait_secs);
+		hasher.write_u64(self.enemy_spawn_wait_coef_percent as u64);
+		hasher.write_u64(self.endless_enemy_archetypes.len() as u64);
+		for name in &self.endless_enemy_archetypes {
+			hasher.write_str(name);
+		}
+		
+		hasher.write_f32(self.combo_multiplier_step);
+		hasher.write_f32(self.combo_multiplier_max);
+		hasher.write_f32(self.combo_decay_delay_secs);
+		hasher.write_f32(self.combo_decay_per_sec);
+		hasher.write_f32(self.survival_score_interval_secs);
+		hasher.write_u64(self.survival_score_per_interval as u64);
+		
+	}
+}
+
+
+
+pub fn get_program_settings_path() -> PathBuf {
+	get_program_file_path("assets/settings.txt")
+}
+
+
+
+// same layout as the gui files: one `key: value` per line, `//` starts a comment
+// the archetypes and weapons are only used to check the names that the settings refer to, and the custom difficulty's effect on the archetypes
+pub fn load_program_settings(path: &Path, enemy_archetypes: &[EnemyArchetype], weapons: &[WeaponDefinition]) -> Result<ProgramSettings> {
+	let mut output = ProgramSettings::new();
+	
+	let file_string = fs::read_to_string(path).with_context(|| format!("Could not read settings file {path:?}"))?;
+	for line in gui::load::parse_key_value_lines(&file_string, path) {
+		let (i, field_name, field_value) = line?;
+		
+		let parse_f32 = || gui::load::parse_value_to_f32(field_value, i, path);
+		let parse_positive_f32 = || parse_value_to_positive_f32(field_value, i, path);
+		let parse_timer_secs = || parse_value_to_timer_secs(field_value, i, path);
+		let parse_usize = || field_value.parse().with_context(|| format!("Invalid whole number \"{field_value}\" in {path:?} line {i}"));
+		match field_name {
+			
+			"player acceleration"        => output.player_acceleration        = parse_f32()?,
+			"player drag coef"           => output.player_drag_coef           = parse_positive_f32()?,
+			"player size"                => output.player_size                = parse_positive_f32()?,
+			
+			"player max health"          => output.player_max_health          = parse_positive_f32()?,
+			"player invincibility secs"  => output.player_invincibility_secs  = parse_f32()?,
+			"player flash interval secs" => output.player_flash_interval_secs = parse_positive_f32()?,
+			"player regen delay secs"    => output.player_regen_delay_secs    = parse_f32()?,
+			"player regen per sec"       => output.player_regen_per_sec       = parse_f32()?,
+			"player knockback speed"     => output.player_knockback_speed     = parse_f32()?,
+			
+			"starting weapon" => {
+				if get_weapon_index(weapons, field_value).is_none() {
+					return Err(Error::msg(format!("Unknown weapon \"{field_value}\" in {path:?} line {i}")));
+				}
+				output.starting_weapon = field_value.to_string();
+			}
+			"bullet size"                => output.bullet_size                = parse_positive_f32()?,
+			
+			"pickup drop chance" => {
+				output.pickup_drop_chance = parse_f32()?;
+				if !(0. ..=1.).contains(&output.pickup_drop_chance) {
+					return Err(Error::msg(format!("Invalid pickup drop chance \"{field_value}\" in {path:?} line {i} (must be between 0 and 1)")));
+				}
+			}
+			"pickup size"                => output.pickup_size                = parse_positive_f32()?,
+			"pickup speed"               => output.pickup_speed               = parse_f32()?,
+			"pickup lifetime secs"       => output.pickup_lifetime_secs       = parse_positive_f32()?,
+			"pickup blink secs"          => output.pickup_blink_secs          = parse_f32()?,
+			"pickup heal amount"         => output.pickup_heal_amount         = parse_f32()?,
+			"pickup score bonus"         => output.pickup_score_bonus         = parse_usize()?,
+			"power up duration secs"     => output.power_up_duration_secs     = parse_positive_f32()?,
+			"rapid fire wait coef"       => output.rapid_fire_wait_coef       = parse_positive_f32()?,
+			"spread shot angle degrees"  => output.spread_shot_angle_degrees  = parse_f32()?,
+			
+			"enemy spawn wait secs"      => output.enemy_spawn_wait_secs      = parse_timer_secs()?,
+			"enemy spawn wait coef percent" => {
+				output.enemy_spawn_wait_coef_percent = field_value.parse().with_context(|| format!("Invalid whole number \"{field_value}\" in {path:?} line {i}"))?;
+				if output.enemy_spawn_wait_coef_percent == 0 {
+					return Err(Error::msg(format!("Invalid enemy spawn wait coef percent \"{field_value}\" in {path:?} line {i} (must be above 0)")));
+				}
+			}
+			"endless enemy archetypes" => {
+				let names = field_value.split_whitespace().map(String::from).collect::<Vec<_>>();
+				if names.is_empty() {
+					return Err(Error::msg(format!("No endless enemy archetypes given in {path:?} line {i}")));
+				}
+				if let Some(name) = names.iter().find(|name| get_enemy_archetype_index(enemy_archetypes, name).is_none()) {
+					return Err(Error::msg(format!("Unknown enemy archetype \"{name}\" in {path:?} line {i}")));
+				}
+				output.endless_enemy_archetypes = names;
+			}
+			
+			"combo multiplier step"        => output.combo_multiplier_step        = parse_f32()?,
+			"combo multiplier max"         => output.combo_multiplier_max         = parse_f32()?,
+			"combo decay delay secs"       => output.combo_decay_delay_secs       = parse_f32()?,
+			"combo decay per sec"          => output.combo_decay_per_sec          = parse_f32()?,
+			"survival score interval secs" => output.survival_score_interval_secs = parse_timer_secs()?,
+			"survival score per interval"  => output.survival_score_per_interval  = parse_usize()?,
+			
+			"custom difficulty enemy speed coef"              => output.custom_difficulty.enemy_speed_coef              = parse_positive_f32()?,
+			"custom difficulty enemy fire rate coef"          => output.custom_difficulty.enemy_fire_rate_coef          = parse_positive_f32()?,
+			"custom difficulty enemy spawn acceleration coef" => output.custom_difficulty.enemy_spawn_acceleration_coef = parse_f32()?,
+			"custom difficulty enemy bullet speed coef"       => output.custom_difficulty.enemy_bullet_speed_coef       = parse_positive_f32()?,
+			"custom difficulty player health coef"            => output.custom_difficulty.player_health_coef            = parse_positive_f32()?,
+			
+			"camera fit mode" => output.camera_fit_mode = CameraFitMode::from_name(field_value).with_context(|| format!("Invalid camera fit mode \"{field_value}\" in {path:?} line {i} (must be fit, fill or stretch)"))?,
+			"camera zoom" => {
+				output.camera_zoom = parse_positive_f32()?;
+				if output.camera_zoom < 1. {
+					return Err(Error::msg(format!("Invalid camera zoom \"{field_value}\" in {path:?} line {i} (must be at least 1)")));
+				}
+			}
+			"camera shake max offset"      => output.camera_shake_max_offset      = parse_f32()?,
+			"camera shake frequency"       => output.camera_shake_frequency       = parse_f32()?,
+			"camera trauma decay per sec"  => output.camera_trauma_decay_per_sec  = parse_f32()?,
+			"camera trauma per kill"       => output.camera_trauma_per_kill       = parse_f32()?,
+			"camera trauma per player hit" => output.camera_trauma_per_player_hit = parse_f32()?,
+			
+			"crosshair size" => output.crosshair_size = parse_f32()?,
+			
+			_ => return Err(Error::msg(format!("Invalid settings field \"{field_name}\" in {path:?} line {i}"))),
+		}
+		
+	}
+	
+	// the defaults still have to point at things that exist
+	if get_weapon_index(weapons, &output.starting_weapon).is_none() {
+		return Err(Error::msg(format!("Could not find weapon \"{}\" (used by the default starting weapon)", output.starting_weapon)));
+	}
+	if let Some(name) = output.endless_enemy_archetypes.iter().find(|name| get_enemy_archetype_index(enemy_archetypes, name).is_none()) {
+		return Err(Error::msg(format!("Could not find enemy archetype \"{name}\" (used by the default endless enemy archetypes)")));
+	}
+	for archetype in enemy_archetypes {
+		output.custom_difficulty.check_archetype(archetype).with_context(|| format!("Invalid custom difficulty enemy fire rate coef in {path:?}"))?;
+	}
+	Ok(output)
+}
+
+
+
+pub fn parse_value_to_positive_f32(value: &str, line: usize, path: &Path) -> Result<f32> {
+	let output = gui::load::parse_value_to_f32(value, line, path)?;
+	if output <= 0. {
+		return Err(Error::msg(format!("Invalid value \"{value}\" in {path:?} line {line} (must be above 0)")));
+	}
+	Ok(output)
+}
+
+
+
+// for the durations of `LoopingTimer`s, which can only loop once per tick
+pub fn parse_value_to_timer_secs(value: &str, line: usize, path: &Path) -> Result<f32> {
+	let output = gui::load::parse_value_to_f32(value, line, path)?;
+	if output < program_settings::TICK_DURATION_SECS {
+		return Err(Error::msg(format!("Invalid value \"{value}\" in {path:?} line {line} (must be at least one tick, {} secs)", program_settings::TICK_DURATION_SECS)));
+	}
+	Ok(output)
+}
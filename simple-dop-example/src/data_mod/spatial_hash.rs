@@ -0,0 +1,78 @@
+use crate::prelude::*;
+
+
+
+// uniform grid over the unit arena, rebuilt from scratch every tick
+// anything outside the arena is put in the nearest edge cell, so queries still find it
+pub struct SpatialHash {
+	pub cells_per_side: usize,
+	// item indices grouped by cell, the items in cell `c` are `item_indices[cell_starts[c] .. cell_starts[c + 1]]`
+	pub cell_starts: Vec<usize>,
+	pub item_indices: Vec<usize>,
+	// scratch buffers, kept around to avoid allocating every tick
+	pub item_cells: Vec<usize>,
+	pub cell_fill_positions: Vec<usize>,
+}
+
+impl SpatialHash {
+	pub fn new(cells_per_side: usize) -> Self {
+		Self {
+			cells_per_side,
+			cell_starts: vec!(0; cells_per_side * cells_per_side + 1),
+			item_indices: vec!(),
+			item_cells: vec!(),
+			cell_fill_positions: vec!(),
+		}
+	}
+	
+	pub fn get_cell_coords(&self, pos: Vec2) -> (usize, usize) {
+		let max_coord = (self.cells_per_side - 1) as f32;
+		let x = (pos.x * self.cells_per_side as f32).floor().clamp(0., max_coord);
+		let y = (pos.y * self.cells_per_side as f32).floor().clamp(0., max_coord);
+		(x as usize, y as usize)
+	}
+	
+	// item indices are the positions' indices in the iterator
+	pub fn rebuild(&mut self, positions: impl Iterator<Item = Vec2>) {
+		let cell_count = self.cells_per_side * self.cells_per_side;
+		self.cell_starts.clear();
+		self.cell_starts.resize(cell_count + 1, 0);
+		
+		// count items per cell
+		self.item_cells.clear();
+		for pos in positions {
+			let (x, y) = self.get_cell_coords(pos);
+			let cell = y * self.cells_per_side + x;
+			self.item_cells.push(cell);
+			self.cell_starts[cell + 1] += 1;
+		}
+		for i in 1 ..= cell_count {
+			self.cell_starts[i] += self.cell_starts[i - 1];
+		}
+		
+		// place items
+		self.cell_fill_positions.clear();
+		self.cell_fill_positions.extend_from_slice(&self.cell_starts[..cell_count]);
+		self.item_indices.clear();
+		self.item_indices.resize(self.item_cells.len(), 0);
+		for (item_index, &cell) in self.item_cells.iter().enumerate() {
+			self.item_indices[self.cell_fill_positions[cell]] = item_index;
+			self.cell_fill_positions[cell] += 1;
+		}
+		
+	}
+	
+	// returns every item whose cell overlaps the square around `pos`, callers still have to do the exact distance check
+	pub fn query(&self, pos: Vec2, radius: f32) -> impl Iterator<Item = usize> + '_ {
+		let (min_x, min_y) = self.get_cell_coords(pos - radius);
+		let (max_x, max_y) = self.get_cell_coords(pos + radius);
+		(min_y ..= max_y).flat_map(move |y| {
+			// cells in a row are contiguous, so each row is a single slice
+			let row_start = y * self.cells_per_side;
+			let start = self.cell_starts[row_start + min_x];
+			let end = self.cell_starts[row_start + max_x + 1];
+			self.item_indices[start..end].iter().copied()
+		})
+	}
+	
+}
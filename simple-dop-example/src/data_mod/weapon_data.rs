@@ -0,0 +1,140 @@
+use crate::prelude::*;
+use std::fs;
+
+
+
+// one of the player's weapons, loaded from `assets/weapons/<name>.txt`
+#[derive(Debug, Clone)]
+pub struct WeaponDefinition {
+	pub name: String,
+	// the number key that switches to this weapon
+	pub slot: u8,
+	pub fire_wait_secs: f32,
+	pub bullet_speed: f32,
+	pub bullet_damage: f32,
+	pub bullet_count: usize,
+	// when above 0 the bullets fan out over this angle, otherwise they're fired side by side `bullet_spacing` apart
+	pub spread_angle_degrees: f32,
+	pub bullet_spacing: f32,
+	// how many enemies each bullet can go through before it's destroyed
+	pub pierce_count: u32,
+	// the weapon that a weapon upgrade pickup turns this one into
+	pub upgrade: Option<String>,
+}
+
+impl WeaponDefinition {
+	// values for any keys that the file leaves out
+	pub fn new(name: impl Into<String>) -> Self {
+		Self {
+			name: name.into(),
+			slot: 0,
+			fire_wait_secs: 0.25,
+			bullet_speed: 0.6,
+			bullet_damage: 1.,
+			bullet_count: 1,
+			spread_angle_degrees: 0.,
+			bullet_spacing: 0.02,
+			pierce_count: 0,
+			upgrade: None,
+		}
+	}
+	// see `replay_data::get_simulation_hash()`
+	pub fn write_simulation_fields(&self, hasher: &mut StableHasher) {
+		hasher.write_str(&self.name);
+		hasher.write_u64(self.slot as u64);
+		hasher.write_f32(self.fire_wait_secs);
+		hasher.write_f32(self.bullet_speed);
+		hasher.write_f32(self.bullet_damage);
+		hasher.write_u64(self.bullet_count as u64);
+		hasher.write_f32(self.spread_angle_degrees);
+		hasher.write_f32(self.bullet_spacing);
+		hasher.write_u64(self.pierce_count as u64);
+		match &self.upgrade {
+			Some(upgrade) => {
+				hasher.write_u64(1);
+				hasher.write_str(upgrade);
+			}
+			None => hasher.write_u64(0),
+		}
+	}
+}
+
+
+
+pub fn get_weapons_path() -> PathBuf {
+	get_program_file_path("assets/weapons")
+}
+
+
+
+// loads every `.txt` file in the folder, the file name is the weapon's name
+// the output is sorted by slot so that weapon indices don't depend on the filesystem
+pub fn load_weapons(folder: impl AsRef<Path>) -> Result<Vec<WeaponDefinition>> {
+	let folder = folder.as_ref();
+	let mut output = vec!();
+	let mut errors = vec!();
+	for entry in fs::read_dir(folder).with_context(|| format!("Could not read weapons folder {folder:?}"))? {
+		let path = entry?.path();
+		if gui::load::get_file_extension(&path).ok() != Some("txt") {continue;}
+		match load_weapon(&path) {
+			Result::Ok (weapon) => output.push(weapon),
+			Result::Err (err) => errors.push(err),
+		}
+	}
+	if !errors.is_empty() {
+		return Err(PackagedErrors::new(errors).into());
+	}
+	output.sort_by_key(|weapon| weapon.slot);
+	
+	for (i, weapon) in output.iter().enumerate() {
+		if i > 0 && output[i - 1].slot == weapon.slot {
+			return Err(Error::msg(format!("Weapons \"{}\" and \"{}\" both use slot {}", output[i - 1].name, weapon.name, weapon.slot)));
+		}
+		let Some(upgrade) = &weapon.upgrade else {continue;};
+		if get_weapon_index(&output, upgrade).is_none() {
+			return Err(Error::msg(format!("Weapon \"{}\" upgrades to unknown weapon \"{upgrade}\"", weapon.name)));
+		}
+	}
+	Ok(output)
+}
+
+
+
+// same layout as the gui files: one `key: value` per line, `//` starts a comment
+pub fn load_weapon(path: &Path) -> Result<WeaponDefinition> {
+	let name = gui::load::get_file_name(path)?;
+	let mut output = WeaponDefinition::new(name);
+	
+	let file_string = fs::read_to_string(path).with_context(|| format!("Could not read weapon file {path:?}"))?;
+	for line in gui::load::parse_key_value_lines(&file_string, path) {
+		let (i, field_name, field_value) = line?;
+		
+		match field_name {
+			"slot"           => output.slot                 = field_value.parse().with_context(|| format!("Invalid slot \"{field_value}\" in {path:?} line {i}"))?,
+			"fire wait"      => output.fire_wait_secs       = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"bullet speed"   => output.bullet_speed         = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"bullet damage"  => output.bullet_damage        = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"bullet count"   => output.bullet_count         = field_value.parse().with_context(|| format!("Invalid bullet count \"{field_value}\" in {path:?} line {i}"))?,
+			"spread angle"   => output.spread_angle_degrees = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"bullet spacing" => output.bullet_spacing       = gui::load::parse_value_to_f32(field_value, i, path)?,
+			"pierce"         => output.pierce_count         = field_value.parse().with_context(|| format!("Invalid pierce count \"{field_value}\" in {path:?} line {i}"))?,
+			"upgrade"        => output.upgrade              = Some(field_value.to_string()),
+			_ => return Err(Error::msg(format!("Invalid weapon field \"{field_name}\" in {path:?} line {i}"))),
+		}
+		
+	}
+	
+	if output.fire_wait_secs <= 0. {
+		return Err(Error::msg(format!("Weapon {path:?} must have a fire wait above 0")));
+	}
+	if output.bullet_count == 0 {
+		return Err(Error::msg(format!("Weapon {path:?} must fire at least one bullet")));
+	}
+	Ok(output)
+}
+
+
+
+pub fn get_weapon_index(weapons: &[WeaponDefinition], name: &str) -> Option<usize> {
+	weapons.iter().position(|weapon| weapon.name == name)
+}
@@ -11,6 +11,11 @@ pub struct GuiLoadingData<'a> {
 pub fn init_all_guis(program_data: &mut ProgramData) -> Result<()> {
 	program_data.main_menu_data.gui = init_single_gui("assets/gui/main menu", update_for_main_menu::set_click_fns, &program_data.textures)?;
 	program_data.playing_data.gui = init_single_gui("assets/gui/playing", update_for_playing::set_click_fns, &program_data.textures)?;
+	program_data.game_over_data.gui = init_single_gui("assets/gui/game over", update_for_game_over::set_click_fns, &program_data.textures)?;
+	program_data.name_entry_data.gui = init_single_gui("assets/gui/name entry", update_for_name_entry::set_click_fns, &program_data.textures)?;
+	program_data.high_scores_data.gui = init_single_gui("assets/gui/high scores", update_for_high_scores::set_click_fns, &program_data.textures)?;
+	program_data.replay_data.gui = init_single_gui("assets/gui/replay", update_for_replay::set_click_fns, &program_data.textures)?;
+	program_data.options_data.gui = init_single_gui("assets/gui/options", update_for_options::set_click_fns, &program_data.textures)?;
 	Ok(())
 }
 
@@ -302,7 +302,7 @@ impl RealArea {
 
 
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Default)]
 pub struct GuiKeyboardData {
 	pub shift_is_pressed: bool,
 	pub control_is_pressed: bool,
@@ -311,7 +311,7 @@ pub struct GuiKeyboardData {
 	pub control_keys_just_pressed: Vec<ControlKey>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone)]
 pub enum ControlKey {
 	
 	Up,
@@ -45,9 +45,12 @@ pub fn load_gui_elements_in_folder<CustomData, LoadingFnsImpl: LoadingFns<Custom
 				break;
 			}
 		}
-		let name_clone = new_gui_element.name.clone();
 		by_layer_output.insert(insert_i, new_gui_element);
-		by_name_output.insert(name_clone, insert_i);
+	}
+	
+	// inserting can shift elements that are already in the list, so the indices are only known at the end
+	for (i, element) in by_layer_output.iter().enumerate() {
+		by_name_output.insert(element.name.clone(), i);
 	}
 	
 	Ok((by_layer_output, by_name_output))
@@ -66,21 +69,15 @@ pub fn load_gui_element<CustomData, LoadingFnsImpl: LoadingFns<CustomData, Loadi
 	let mut output = GuiElement::new(&element_name, vec!(), HashMap::new(), custom_data_fn);
 	
 	let file_string = fs::read_to_string(path)?;
-	for (i, mut line) in file_string.split('\n').enumerate() {
+	for line in parse_key_value_lines(&file_string, path) {
 		
-		if let Some(comment_index) = line.find("//") {
-			line = &line[0..comment_index];
-		}
-		line = line.trim();
-		if line.is_empty() {continue;}
-		
-		let Some(colon_index) = line.find(':') else {
-			errors.push(GuiError::MissingColon {line: i, path: path.to_path_buf()}.into());
-			continue;
+		let (i, field_name, field_value) = match line {
+			Result::Ok (line) => line,
+			Result::Err (new_error) => {
+				errors.push(new_error);
+				continue;
+			}
 		};
-		
-		let field_name = line[..colon_index].trim();
-		let field_value = line[(colon_index + 1)..].trim();
 		match apply_field_to_element::<CustomData, LoadingFnsImpl, LoadingData>(&mut output, field_name, field_value, i, path, loading_data) {
 			Result::Ok (()) => {},
 			Result::Err (new_error) => errors.push(new_error),
@@ -102,6 +99,24 @@ pub fn load_gui_element<CustomData, LoadingFnsImpl: LoadingFns<CustomData, Loadi
 
 
 
+// shared by every `key: value` file (gui elements and the data files), yields `(line index, key, value)` for each line that isn't blank or a comment
+// `//` starts a comment, and a line without a colon gives an error but doesn't stop the lines after it
+pub fn parse_key_value_lines<'a>(file_string: &'a str, path: &'a Path) -> impl Iterator<Item = Result<(usize, &'a str, &'a str)>> + 'a {
+	file_string.split('\n').enumerate().filter_map(move |(i, mut line)| {
+		if let Some(comment_index) = line.find("//") {
+			line = &line[0..comment_index];
+		}
+		line = line.trim();
+		if line.is_empty() {return None;}
+		let Some(colon_index) = line.find(':') else {
+			return Some(Err(GuiError::MissingColon {line: i, path: path.to_path_buf()}.into()));
+		};
+		Some(Ok((i, line[..colon_index].trim(), line[(colon_index + 1)..].trim())))
+	})
+}
+
+
+
 pub fn get_file_extension (path: &Path) -> Result<&str> {
 	let ext = path.extension().ok_or_else(|| GuiError::InvalidFileName {path: path.to_path_buf()})?;
 	let ext = ext.to_str().ok_or_else(|| GuiError::InvalidFileName {path: path.to_path_buf()})?;
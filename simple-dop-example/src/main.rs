@@ -15,19 +15,17 @@ pub mod program_settings {
 	pub const MAIN_MENU_WAIT_DURATION: Duration = Duration::from_secs(2);
 	pub const PLAYING_PAUSE_MENU_FADE_DURATION: Duration = Duration::from_millis(200);
 	
-	pub const PLAYER_ACCELERATION: f32 = 1.7;
-	pub const PLAYER_DRAG_COEF: f32 = 0.01;
-	pub const ENEMY_SPEED: f32 = 0.2;
+	pub const TICKS_PER_SECOND: u32 = 60;
+	pub const TICK_DURATION_SECS: f32 = 1. / TICKS_PER_SECOND as f32;
+	pub const MAX_TICKS_PER_FRAME: u32 = 10;
+	pub const REPLAY_FAST_FORWARD_SPEED: f32 = 4.;
 	
-	pub const PLAYER_SIZE: f32 = 0.05;
-	pub const ENEMY_SIZE: f32 = 0.05;
+	pub const SPATIAL_HASH_CELLS_PER_SIDE: usize = 16;
 	
-	pub const PLAYER_BULLET_SPEED: f32 = 0.01;
-	pub const ENEMY_BULLET_SPEED: f32 = 0.01;
+	pub const HIGH_SCORE_COUNT: usize = 10;
 	
-	pub const ENEMY_SPAWN_WAIT_SECS: f32 = 1.;
-	pub const ENEMY_SPAWN_WAIT_COEF_PERCENT: u32 = 98;
-	pub const ENEMY_SHOOT_WAIT_SECS: f32 = 1.;
+	// particles past this are dropped instead of growing the pool
+	pub const MAX_PARTICLES: usize = 4096;
 	
 }
 
@@ -35,6 +33,7 @@ pub mod program_settings {
 
 use prelude::*;
 use notan::draw::{DrawConfig, CreateFont};
+use std::collections::HashMap;
 
 
 
@@ -45,6 +44,7 @@ pub mod gui_mod;
 pub mod gui_integration_mod;
 pub mod utils;
 pub mod custom_impls;
+pub mod arena_coords;
 
 
 
@@ -52,11 +52,26 @@ pub mod prelude {
 	
 	pub use crate::{
 		data_mod::general_data::*,
+		data_mod::input_data::*,
+		data_mod::replay_data::*,
+		data_mod::high_score_data::*,
+		data_mod::spatial_hash::*,
+		data_mod::entity_data::*,
+		data_mod::enemy_data::*,
+		data_mod::level_data::*,
+		data_mod::pickup_data::*,
+		data_mod::weapon_data::*,
+		data_mod::settings_data::*,
+		data_mod::difficulty_data::*,
+		data_mod::action_data::*,
+		data_mod::particle_data::*,
+		data_mod::camera_data::*,
 		update_mod::*,
 		utils::*,
 		program_settings,
 		gui_mod as gui,
 		custom_impls::*,
+		arena_coords::{self, *},
 	};
 	pub use crate::gui_integration_mod::{self, prelude::*};
 	pub use std::{path::*, time::{Duration, Instant}};
@@ -110,12 +125,58 @@ pub fn init(gfx: &mut Graphics) -> Result<ProgramData> {
 	
 	// General Data
 	
+	let command_line_args = parse_command_line_args().context("Could not parse command line arguments")?;
+	
+	// load enemy archetypes
+	let enemy_archetypes = load_enemy_archetypes(get_enemy_archetypes_path()).context("Could not load enemy archetypes")?;
+	
+	// load weapons
+	let weapons = load_weapons(get_weapons_path()).context("Could not load weapons")?;
+	
+	// load particle emitters
+	let particle_emitters = load_particle_emitters(get_particle_emitters_path()).context("Could not load particle emitters")?;
+	
+	// load settings
+	let settings = load_program_settings(&get_program_settings_path(), &enemy_archetypes, &weapons).context("Could not load settings")?;
+	
 	// load textures
-	let textures = Textures {
+	let mut textures = Textures {
 		player       : load_texture(get_program_file_path("assets/textures/player.png"       ), gfx)?,
-		enemy        : load_texture(get_program_file_path("assets/textures/enemy.png"        ), gfx)?,
 		player_bullet: load_texture(get_program_file_path("assets/textures/player_bullet.png"), gfx)?,
 		enemy_bullet : load_texture(get_program_file_path("assets/textures/enemy_bullet.png" ), gfx)?,
+		enemies: HashMap::new(),
+		pickups: HashMap::new(),
+	};
+	for archetype in &enemy_archetypes {
+		if textures.enemies.contains_key(&archetype.texture_name) {continue;}
+		let path = get_program_file_path(format!("assets/textures/{}.png", archetype.texture_name));
+		let texture = load_texture(&path, gfx).with_context(|| format!("Could not load texture {path:?} for enemy archetype \"{}\"", archetype.name))?;
+		textures.enemies.insert(archetype.texture_name.clone(), texture);
+	}
+	for kind in PickupKind::ALL {
+		let path = get_program_file_path(format!("assets/textures/{}.png", kind.get_texture_name()));
+		textures.pickups.insert(kind, load_texture(path, gfx)?);
+	}
+	
+	// load high scores
+	let high_scores = load_high_scores(get_high_scores_path()).unwrap_or_else(|err| {
+		println!("Warning: could not load high scores, starting with an empty table: {err:?}");
+		vec!()
+	});
+	
+	// load input bindings (the defaults are written out the first time so that there's a file to edit)
+	let input_bindings_path = get_input_bindings_path();
+	let input_bindings = if input_bindings_path.exists() {
+		load_input_bindings(&input_bindings_path).unwrap_or_else(|err| {
+			println!("Warning: could not load input bindings, using the defaults: {err:#}");
+			InputBindings::new()
+		})
+	} else {
+		let input_bindings = InputBindings::new();
+		if let Err(err) = save_input_bindings(&input_bindings_path, &input_bindings) {
+			println!("Warning: could not save the default input bindings: {err:#}");
+		}
+		input_bindings
 	};
 	
 	// load font
@@ -137,10 +198,31 @@ pub fn init(gfx: &mut Graphics) -> Result<ProgramData> {
 		mode: ProgramMode::MainMenu,
 		main_menu_data: MainMenuData::empty(),
 		playing_data: PlayingData::empty(),
+		replay_data: ReplayData::empty(),
+		game_over_data: GameOverData::empty(),
+		name_entry_data: NameEntryData::empty(),
+		high_scores_data: HighScoresData::empty(),
+		options_data: OptionsData::empty(),
+		
+		high_scores,
+		input_bindings,
 		
 	};
 	
+	output.playing_data.loaded_settings = settings;
 	output.main_menu_data.reset();
+	output.playing_data.seed_override = command_line_args.seed;
+	if let Some(level_name) = &command_line_args.level_name {
+		let level = load_level(level_name, &enemy_archetypes).with_context(|| format!("Could not load level \"{level_name}\""))?;
+		output.playing_data.selected_level = Some(level);
+	}
+	output.playing_data.loaded_enemy_archetypes = enemy_archetypes;
+	output.playing_data.weapons = weapons;
+	output.playing_data.particle_emitters = particle_emitters;
+	if let Some(replay_path) = &command_line_args.replay_path {
+		let replay = load_replay(replay_path)?;
+		update_for_replay::start_replay(&mut output, replay)?;
+	}
 	
 	// load gui
 	init_all_guis(&mut output)?;
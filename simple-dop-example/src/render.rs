@@ -1,5 +1,6 @@
 use crate::prelude::*;
 use notan::draw::{CreateDraw, Draw, DrawImages, DrawShapes};
+use std::collections::HashMap;
 
 
 
@@ -45,44 +46,67 @@ pub fn render(gfx: &mut Graphics, program_data: &mut ProgramData) -> Result<()>
 		
 		
 		ProgramMode::Playing => {
+			
 			let playing_data = &program_data.playing_data;
+			let crosshair_pos = if playing_data.pause_data.is_paused {None} else {Some(playing_data.pending_tick_input.aim_target)};
+			render_arena(playing_data, crosshair_pos, textures, &mut draw, screen_size);
+			
+			render_gui(&program_data.playing_data.gui, program_data, &mut draw, screen_size)?;
 			
-			// background
-			draw.clear(Color::BLACK);
-			let (on_screen_pos, on_screen_size) = arena_placement_to_screen_placement(
-				Vec2::new(0., 0.),
-				Vec2::new(1., 1.),
-				screen_size
-			);
-			draw
-				.rect(on_screen_pos, on_screen_size)
-				.color(Color::from_rgb(0.2, 0.2, 0.2));
-			
-			// player
-			let (on_screen_pos, on_screen_size) = arena_placement_to_screen_placement(
-				playing_data.player_pos - program_settings::PLAYER_SIZE * 0.5,
-				Vec2::new(program_settings::PLAYER_SIZE, program_settings::PLAYER_SIZE),
-				screen_size
-			);
-			draw
-				.image(&program_data.textures.player)
-				.position(on_screen_pos.0, on_screen_pos.1)
-				.size(on_screen_size.0, on_screen_size.1);
-			
-			// enemies
-			for enemy in &playing_data.enemies {
-				let (on_screen_pos, on_screen_size) = arena_placement_to_screen_placement(
-					enemy.pos - program_settings::ENEMY_SIZE * 0.5,
-					Vec2::new(program_settings::ENEMY_SIZE, program_settings::ENEMY_SIZE),
-					screen_size
-				);
-				draw
-					.image(&program_data.textures.enemy)
-					.position(on_screen_pos.0, on_screen_pos.1)
-					.size(on_screen_size.0, on_screen_size.1);
+		}
+		
+		
+		
+		ProgramMode::GameOver => {
+			
+			render_arena(&program_data.playing_data, None, textures, &mut draw, screen_size);
+			
+			render_gui(&program_data.game_over_data.gui, program_data, &mut draw, screen_size)?;
+			
+		}
+		
+		
+		
+		ProgramMode::NameEntry => {
+			
+			render_arena(&program_data.playing_data, None, textures, &mut draw, screen_size);
+			
+			render_gui(&program_data.name_entry_data.gui, program_data, &mut draw, screen_size)?;
+			
+		}
+		
+		
+		
+		ProgramMode::HighScores => {
+			
+			draw.clear(Color::from_rgba(0.5, 0.42, 0.42, 1.0));
+			
+			render_gui(&program_data.high_scores_data.gui, program_data, &mut draw, screen_size)?;
+			
+		}
+		
+		
+		
+		ProgramMode::Replay => {
+			
+			// show where the player was last aiming while firing
+			render_arena(&program_data.playing_data, program_data.replay_data.last_fired_aim_target, textures, &mut draw, screen_size);
+			
+			render_gui(&program_data.replay_data.gui, program_data, &mut draw, screen_size)?;
+			
+		}
+		
+		
+		
+		ProgramMode::Options => {
+			
+			if program_data.options_data.return_to_playing {
+				render_arena(&program_data.playing_data, None, textures, &mut draw, screen_size);
+			} else {
+				draw.clear(Color::from_rgba(0.5, 0.42, 0.42, 1.0));
 			}
 			
-			render_gui(&program_data.playing_data.gui, program_data, &mut draw, screen_size)?;
+			render_gui(&program_data.options_data.gui, program_data, &mut draw, screen_size)?;
 			
 		}
 		
@@ -99,6 +123,150 @@ pub fn render(gfx: &mut Graphics, program_data: &mut ProgramData) -> Result<()>
 
 
 
+// `crosshair_pos` is where the player is aiming in the arena, if it should be shown
+pub fn render_arena(playing_data: &PlayingData, crosshair_pos: Option<Vec2>, textures: &Textures, draw: &mut Draw, screen_size: UVec2) {
+	let tick_alpha = playing_data.get_tick_alpha();
+	let view = playing_data.camera.get_view(&playing_data.settings, tick_alpha, screen_size);
+	
+	// background
+	draw.clear(Color::BLACK);
+	let (on_screen_pos, on_screen_size) = arena_placement_to_screen_placement(
+		Vec2::new(0., 0.),
+		Vec2::new(1., 1.),
+		&view
+	);
+	draw
+		.rect(on_screen_pos, on_screen_size)
+		.color(Color::from_rgb(0.2, 0.2, 0.2));
+	
+	// player
+	let player_pos = playing_data.prev_player_pos.lerp(playing_data.player_pos, tick_alpha);
+	let flash_phase = (playing_data.invincibility_secs_left / playing_data.settings.player_flash_interval_secs) as u32;
+	let is_flashed_out = playing_data.invincibility_secs_left > 0. && flash_phase % 2 == 1;
+	let (on_screen_pos, on_screen_size) = arena_placement_to_screen_placement(
+		player_pos - playing_data.settings.player_size * 0.5,
+		Vec2::new(playing_data.settings.player_size, playing_data.settings.player_size),
+		&view
+	);
+	draw
+		.image(&textures.player)
+		.position(on_screen_pos.0, on_screen_pos.1)
+		.size(on_screen_size.0, on_screen_size.1)
+		.alpha(if is_flashed_out {0.2} else {1.});
+	
+	// enemies
+	let enemies = &playing_data.enemies;
+	for i in 0..enemies.len() {
+		let enemy_pos = enemies.prev_pos[i].lerp(enemies.pos[i], tick_alpha);
+		let size = enemies.size[i];
+		let (on_screen_pos, on_screen_size) = arena_placement_to_screen_placement(
+			enemy_pos - size * 0.5,
+			Vec2::new(size, size),
+			&view
+		);
+		let archetype = &playing_data.enemy_archetypes[enemies.archetype[i]];
+		let Some(texture) = textures.enemies.get(&archetype.texture_name) else {continue;};
+		draw
+			.image(texture)
+			.position(on_screen_pos.0, on_screen_pos.1)
+			.size(on_screen_size.0, on_screen_size.1);
+	}
+	
+	// pickups
+	let pickups = &playing_data.pickups;
+	for i in 0..pickups.len() {
+		let secs_left = pickups.secs_left[i];
+		let is_blinked_out = secs_left < playing_data.settings.pickup_blink_secs && (secs_left / playing_data.settings.player_flash_interval_secs) as u32 % 2 == 1;
+		if is_blinked_out {continue;}
+		let pickup_pos = pickups.prev_pos[i].lerp(pickups.pos[i], tick_alpha);
+		let (on_screen_pos, on_screen_size) = arena_placement_to_screen_placement(
+			pickup_pos - playing_data.settings.pickup_size * 0.5,
+			Vec2::new(playing_data.settings.pickup_size, playing_data.settings.pickup_size),
+			&view
+		);
+		let Some(texture) = textures.pickups.get(&pickups.kind[i]) else {continue;};
+		draw
+			.image(texture)
+			.position(on_screen_pos.0, on_screen_pos.1)
+			.size(on_screen_size.0, on_screen_size.1);
+	}
+	
+	// bullets
+	render_bullets(&playing_data.player_bullets, &textures.player_bullet, playing_data.settings.bullet_size, tick_alpha, draw, &view);
+	render_bullets(&playing_data.enemy_bullets, &textures.enemy_bullet, playing_data.settings.bullet_size, tick_alpha, draw, &view);
+	
+	// particles
+	render_particles(&playing_data.particles, &playing_data.particle_emitters, tick_alpha, draw, &view);
+	
+	// crosshair
+	if let Some(crosshair_pos) = crosshair_pos {
+		render_crosshair(crosshair_pos, playing_data.settings.crosshair_size, draw, &view);
+	}
+	
+}
+
+
+
+pub fn render_bullets(bullets: &Bullets, texture: &Texture, bullet_size: f32, tick_alpha: f32, draw: &mut Draw, view: &ArenaView) {
+	for (prev_pos, pos) in bullets.prev_pos.iter().zip(&bullets.pos) {
+		let bullet_pos = prev_pos.lerp(*pos, tick_alpha);
+		let (on_screen_pos, on_screen_size) = arena_placement_to_screen_placement(
+			bullet_pos - bullet_size * 0.5,
+			Vec2::new(bullet_size, bullet_size),
+			view
+		);
+		draw
+			.image(texture)
+			.position(on_screen_pos.0, on_screen_pos.1)
+			.size(on_screen_size.0, on_screen_size.1);
+	}
+}
+
+
+
+pub fn render_particles(particles: &Particles, emitters: &HashMap<ParticleEffect, ParticleEmitter>, tick_alpha: f32, draw: &mut Draw, view: &ArenaView) {
+	for i in 0..particles.len() {
+		let Some(emitter) = emitters.get(&particles.effect[i]) else {continue;};
+		// the interpolated age can pass the lifetime just before the next tick removes the particle
+		let t = ((particles.age_secs[i] + program_settings::TICK_DURATION_SECS * tick_alpha) / particles.lifetime_secs[i]).min(1.);
+		let size = emitter.get_size(t);
+		if size <= 0. {continue;}
+		let particle_pos = particles.prev_pos[i].lerp(particles.pos[i], tick_alpha);
+		let (on_screen_pos, on_screen_size) = arena_placement_to_screen_placement(
+			particle_pos - size * 0.5,
+			Vec2::new(size, size),
+			view
+		);
+		draw
+			.circle(on_screen_size.0 * 0.5)
+			.position(on_screen_pos.0 + on_screen_size.0 * 0.5, on_screen_pos.1 + on_screen_size.1 * 0.5)
+			.color(emitter.get_color(t));
+	}
+}
+
+
+
+// four arms around `pos` with a gap in the middle so that the exact aim point stays visible
+pub fn render_crosshair(pos: Vec2, size: f32, draw: &mut Draw, view: &ArenaView) {
+	let arm_length = size * 0.35;
+	let arm_width = size * 0.1;
+	let gap = size * 0.5 - arm_length;
+	let arms = [
+		(Vec2::new(-arm_width * 0.5, -size * 0.5), Vec2::new(arm_width, arm_length)),
+		(Vec2::new(-arm_width * 0.5, gap), Vec2::new(arm_width, arm_length)),
+		(Vec2::new(-size * 0.5, -arm_width * 0.5), Vec2::new(arm_length, arm_width)),
+		(Vec2::new(gap, -arm_width * 0.5), Vec2::new(arm_length, arm_width)),
+	];
+	for (offset, arm_size) in arms {
+		let (on_screen_pos, on_screen_size) = arena_placement_to_screen_placement(pos + offset, arm_size, view);
+		draw
+			.rect(on_screen_pos, on_screen_size)
+			.color(Color::from_rgba(1., 1., 1., 0.8));
+	}
+}
+
+
+
 pub fn render_gui(gui: &GuiElement<CustomGuiData>, program_data: &ProgramData, draw: &mut Draw, screen_size: UVec2) -> Result<()> {
 	
 	// render
@@ -118,30 +286,3 @@ pub fn render_gui(gui: &GuiElement<CustomGuiData>, program_data: &ProgramData, d
 	
 	Ok(())
 }
-
-
-
-pub fn arena_placement_to_screen_placement(arena_pos: Vec2, arena_size: Vec2, screen_size: UVec2) -> ((f32, f32), (f32, f32)) {
-	let top_left = arena_pos_to_screen_pos(arena_pos, screen_size);
-	let bottom_right = arena_pos_to_screen_pos(arena_pos + arena_size, screen_size);
-	let size = (bottom_right.0 - top_left.0, bottom_right.1 - top_left.1);
-	(top_left, size)
-}
-
-
-
-pub fn arena_pos_to_screen_pos(arena_pos: Vec2, screen_size: UVec2) -> (f32, f32) {
-	if screen_size.y < screen_size.x {
-		
-		let mut screen_pos = (arena_pos * screen_size.y as f32).as_ivec2();
-		screen_pos.x += (screen_size.x - screen_size.y) as i32 / 2;
-		(screen_pos.x as f32, screen_pos.y as f32)
-		
-	} else {
-		
-		let mut screen_pos = (arena_pos * screen_size.x as f32).as_ivec2();
-		screen_pos.y += (screen_size.y - screen_size.x) as i32 / 2;
-		(screen_pos.x as f32, screen_pos.y as f32)
-		
-	}
-}
@@ -0,0 +1,143 @@
+use crate::prelude::*;
+use std::f32::consts::TAU;
+
+
+
+// where the enemy is shooting at
+pub struct FiringTarget {
+	pub pos: Vec2,
+	pub vel: Vec2,
+}
+
+
+
+// called once per tick for every enemy, `timer_did_loop` is whether the enemy's shoot timer looped this tick
+pub fn update_enemy_firing(
+	archetype: &EnemyArchetype,
+	state: &mut FiringState,
+	timer_did_loop: bool,
+	origin: Vec2,
+	target: &FiringTarget,
+	dt: f32,
+	bullets: &mut Bullets,
+) {
+	let bullet_speed = archetype.bullet_speed;
+	let new_bullet = |angle| Bullet::new_with_angle(origin, angle, bullet_speed, archetype.bullet_damage);
+	let aim_angle = get_angle(target.pos - origin);
+	match archetype.firing_pattern {
+		
+		FiringPattern::None => {}
+		
+		FiringPattern::Aimed => {
+			if !timer_did_loop {return;}
+			bullets.push(new_bullet(aim_angle));
+		}
+		
+		FiringPattern::AimedWithLead => {
+			if !timer_did_loop {return;}
+			let lead_target = get_lead_target(origin, target, bullet_speed);
+			bullets.push(new_bullet(get_angle(lead_target - origin)));
+		}
+		
+		FiringPattern::Spread {bullet_count, angle_degrees} => {
+			if !timer_did_loop {return;}
+			let angle_radians = angle_degrees.to_radians();
+			for i in 0..bullet_count {
+				// spread evenly from -half to +half of the angle, centered on the target
+				let offset = if bullet_count == 1 {0.} else {i as f32 / (bullet_count - 1) as f32 - 0.5};
+				bullets.push(new_bullet(aim_angle + offset * angle_radians));
+			}
+		}
+		
+		FiringPattern::Spiral {arm_count, degrees_per_shot} => {
+			if !timer_did_loop {return;}
+			for i in 0..arm_count {
+				let angle = state.angle + TAU * i as f32 / arm_count as f32;
+				bullets.push(new_bullet(angle));
+			}
+			state.angle = (state.angle + degrees_per_shot.to_radians()) % TAU;
+		}
+		
+		FiringPattern::Burst {bullet_count, interval_secs} => {
+			if timer_did_loop {
+				state.burst_shots_left = bullet_count;
+				state.burst_timer = 0.;
+			}
+			if state.burst_shots_left == 0 {return;}
+			state.burst_timer -= dt;
+			if state.burst_timer <= 0. {
+				bullets.push(new_bullet(aim_angle));
+				state.burst_shots_left -= 1;
+				state.burst_timer += interval_secs;
+			}
+		}
+		
+		FiringPattern::Ring {bullet_count} => {
+			if !timer_did_loop {return;}
+			for i in 0..bullet_count {
+				let angle = aim_angle + TAU * i as f32 / bullet_count as f32;
+				bullets.push(new_bullet(angle));
+			}
+		}
+		
+	}
+}
+
+
+
+// fires one shot from the player's current weapon towards `target`
+pub fn fire_player_weapon(weapon: &WeaponDefinition, effects: &ActiveEffects, settings: &ProgramSettings, origin: Vec2, target: Vec2, bullets: &mut Bullets) {
+	let aim_angle = get_angle(target - origin);
+	let side_dir = Vec2::from_angle(aim_angle).perp();
+	let spread_shot_angles: &[f32] = if effects.has_spread_shot() {&[-1., 0., 1.]} else {&[0.]};
+	for i in 0..weapon.bullet_count {
+		// -0.5 to 0.5 across the bullets
+		let offset = if weapon.bullet_count == 1 {0.} else {i as f32 / (weapon.bullet_count - 1) as f32 - 0.5};
+		let (angle, start) = if weapon.spread_angle_degrees > 0. {
+			(aim_angle + offset * weapon.spread_angle_degrees.to_radians(), origin)
+		} else {
+			(aim_angle, origin + side_dir * offset * weapon.bullet_spacing * (weapon.bullet_count - 1) as f32)
+		};
+		for spread_shot_offset in spread_shot_angles {
+			let angle = angle + spread_shot_offset * settings.spread_shot_angle_degrees.to_radians();
+			let mut bullet = Bullet::new_with_angle(start, angle, weapon.bullet_speed, weapon.bullet_damage);
+			bullet.pierce_count = weapon.pierce_count;
+			bullets.push(bullet);
+		}
+	}
+}
+
+
+
+pub fn get_angle(dir: Vec2) -> f32 {
+	dir.y.atan2(dir.x)
+}
+
+
+
+// returns where to aim so that a bullet meets a target moving at a constant velocity
+// falls back to the target's current position when the bullet can't catch up
+pub fn get_lead_target(origin: Vec2, target: &FiringTarget, bullet_speed: f32) -> Vec2 {
+	// solve |offset + vel * t| = bullet_speed * t for the smallest positive t
+	let offset = target.pos - origin;
+	let a = target.vel.length_squared() - bullet_speed * bullet_speed;
+	let b = 2. * offset.dot(target.vel);
+	let c = offset.length_squared();
+	let time = if a.abs() < 0.000001 {
+		if b >= 0. {return target.pos;}
+		-c / b
+	} else {
+		let discriminant = b * b - 4. * a * c;
+		if discriminant < 0. {return target.pos;}
+		let root = discriminant.sqrt();
+		let t1 = (-b - root) / (2. * a);
+		let t2 = (-b + root) / (2. * a);
+		match (t1 > 0., t2 > 0.) {
+			(true, true) => t1.min(t2),
+			(true, false) => t1,
+			(false, true) => t2,
+			(false, false) => return target.pos,
+		}
+	};
+	target.pos + target.vel * time
+}
@@ -0,0 +1,110 @@
+use crate::prelude::*;
+
+
+
+// how far two circles overlap, negative when there's a gap between them
+pub fn get_circle_overlap(pos_a: Vec2, radius_a: f32, pos_b: Vec2, radius_b: f32) -> f32 {
+	radius_a + radius_b - (pos_a - pos_b).length()
+}
+
+// direction from `pos_b` to `pos_a`, or an arbitrary direction if they're on top of each other
+pub fn get_push_dir(pos_a: Vec2, pos_b: Vec2) -> Vec2 {
+	(pos_a - pos_b).try_normalize().unwrap_or(Vec2::X)
+}
+
+
+
+// returns how far along the segment (0 to 1) a point moving from `seg_start` to `seg_end` first touches the circle
+// a point that starts inside the circle hits at 0
+pub fn get_segment_circle_hit_time(seg_start: Vec2, seg_end: Vec2, center: Vec2, radius: f32) -> Option<f32> {
+	let offset = seg_start - center;
+	let c = offset.length_squared() - radius * radius;
+	if c <= 0. {return Some(0.);}
+	
+	// solve |offset + seg * t| = radius for the smallest t
+	let seg = seg_end - seg_start;
+	let a = seg.length_squared();
+	let b = 2. * offset.dot(seg);
+	if a == 0. || b >= 0. {return None;}
+	let discriminant = b * b - 4. * a * c;
+	if discriminant < 0. {return None;}
+	let t = (-b - discriminant.sqrt()) / (2. * a);
+	(t <= 1.).then_some(t)
+}
+
+
+
+// same as `get_segment_circle_hit_time()`, but both circles move in a straight line over the same time
+pub fn get_swept_circles_hit_time(start_a: Vec2, end_a: Vec2, radius_a: f32, start_b: Vec2, end_b: Vec2, radius_b: f32) -> Option<f32> {
+	// from b's point of view, a moves along (start_a - start_b) -> (end_a - end_b) and b stays at the origin
+	get_segment_circle_hit_time(start_a - start_b, end_a - end_b, Vec2::ZERO, radius_a + radius_b)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn assert_hit_time(hit_time: Option<f32>, expected: f32) {
+		let hit_time = hit_time.unwrap_or_else(|| panic!("expected a hit at {expected}, got none"));
+		assert!((hit_time - expected).abs() < 1e-5, "expected a hit at {expected}, got {hit_time}");
+	}
+	
+	#[test]
+	fn segment_crossing_the_whole_circle_hits_on_entry() {
+		// both ends are outside the circle, so only the sweep can catch this
+		let hit_time = get_segment_circle_hit_time(Vec2::new(0., 0.5), Vec2::new(1., 0.5), Vec2::new(0.5, 0.5), 0.1);
+		assert_hit_time(hit_time, 0.4);
+	}
+	
+	#[test]
+	fn tangent_segment_hits() {
+		// discriminant is exactly 0 with these values
+		let hit_time = get_segment_circle_hit_time(Vec2::new(0., 0.5), Vec2::new(4., 0.5), Vec2::new(2., 0.), 0.5);
+		assert_hit_time(hit_time, 0.5);
+	}
+	
+	#[test]
+	fn segment_missing_by_an_epsilon_misses() {
+		let hit_time = get_segment_circle_hit_time(Vec2::new(0., 0.5001), Vec2::new(4., 0.5001), Vec2::new(2., 0.), 0.5);
+		assert_eq!(hit_time, None);
+	}
+	
+	#[test]
+	fn segment_starting_inside_hits_at_0() {
+		let hit_time = get_segment_circle_hit_time(Vec2::new(0.1, 0.), Vec2::new(5., 0.), Vec2::ZERO, 0.5);
+		assert_eq!(hit_time, Some(0.));
+	}
+	
+	#[test]
+	fn segment_moving_away_misses() {
+		let hit_time = get_segment_circle_hit_time(Vec2::new(0.6, 0.), Vec2::new(1., 0.), Vec2::ZERO, 0.5);
+		assert_eq!(hit_time, None);
+	}
+	
+	#[test]
+	fn segment_stopping_short_misses() {
+		let hit_time = get_segment_circle_hit_time(Vec2::new(-2., 0.), Vec2::new(-1., 0.), Vec2::ZERO, 0.5);
+		assert_eq!(hit_time, None);
+	}
+	
+	#[test]
+	fn zero_length_segment() {
+		assert_eq!(get_segment_circle_hit_time(Vec2::new(1., 0.), Vec2::new(1., 0.), Vec2::ZERO, 0.5), None);
+		assert_eq!(get_segment_circle_hit_time(Vec2::new(0.2, 0.), Vec2::new(0.2, 0.), Vec2::ZERO, 0.5), Some(0.));
+	}
+	
+	#[test]
+	fn swept_circles_moving_toward_each_other_hit() {
+		let hit_time = get_swept_circles_hit_time(Vec2::new(0., 0.), Vec2::new(1., 0.), 0.1, Vec2::new(1., 0.), Vec2::new(0., 0.), 0.1);
+		assert_hit_time(hit_time, 0.4);
+	}
+	
+	#[test]
+	fn swept_circles_moving_apart_miss() {
+		let hit_time = get_swept_circles_hit_time(Vec2::new(0.5, 0.), Vec2::new(0., 0.), 0.1, Vec2::new(0.8, 0.), Vec2::new(1., 0.), 0.1);
+		assert_eq!(hit_time, None);
+	}
+	
+}
@@ -0,0 +1,217 @@
+use crate::prelude::*;
+use notan::random::rand::Rng;
+
+
+
+// runs the playing simulation without a window, gpu, or gui so that it can be driven by scripted inputs
+pub struct HeadlessRunner {
+	pub playing_data: PlayingData,
+	pub frame_dt: f32,
+	pub frame_count: usize,
+}
+
+impl HeadlessRunner {
+	pub fn new(frame_dt: f32, seed: u64, enemy_archetypes: Vec<EnemyArchetype>, weapons: Vec<WeaponDefinition>, settings: ProgramSettings) -> Self {
+		let mut playing_data = PlayingData::empty();
+		playing_data.loaded_enemy_archetypes = enemy_archetypes;
+		playing_data.weapons = weapons;
+		playing_data.loaded_settings = settings;
+		playing_data.seed_override = Some(seed);
+		playing_data.reset();
+		Self {
+			playing_data,
+			frame_dt,
+			frame_count: 0,
+		}
+	}
+}
+
+
+
+// same as `update_for_playing::update()` minus the gui, returns whether the run is over
+pub fn step(runner: &mut HeadlessRunner, input: &InputSnapshot) -> Result<bool> {
+	let playing_data = &mut runner.playing_data;
+	update_for_playing::process_inputs_before_main(input, playing_data);
+	let run_is_over = update_for_playing::update_simulation(input, playing_data, runner.frame_dt)?;
+	runner.frame_count += 1;
+	Ok(run_is_over)
+}
+
+
+
+// stops at the end of the run, where the real game would switch to the game over screen
+pub fn run_script(runner: &mut HeadlessRunner, inputs: &[InputSnapshot]) -> Result<()> {
+	for input in inputs {
+		let run_is_over = step(runner, input)?;
+		if run_is_over {break;}
+	}
+	Ok(())
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashSet;
+	
+	const FRAME_COUNT: usize = 1200;
+	const SCREEN_SIZE: UVec2 = UVec2::new(1280, 720);
+	
+	fn new_runner(seed: u64) -> HeadlessRunner {
+		let enemy_archetypes = load_enemy_archetypes(get_enemy_archetypes_path()).unwrap();
+		let weapons = load_weapons(get_weapons_path()).unwrap();
+		let settings = load_program_settings(&get_program_settings_path(), &enemy_archetypes, &weapons).unwrap();
+		HeadlessRunner::new(program_settings::TICK_DURATION_SECS, seed, enemy_archetypes, weapons, settings)
+	}
+	
+	// circles around the arena while sweeping the mouse, switching weapons now and then, and pausing once
+	fn get_input_script() -> Vec<InputSnapshot> {
+		let input_bindings = InputBindings::new();
+		let mut prev_keys_down = HashSet::new();
+		let mut prev_mouse_buttons_down = HashSet::new();
+		(0..FRAME_COUNT).map(|i| {
+			let mut input = InputSnapshot::empty(SCREEN_SIZE);
+			input.keys_down.insert([KeyCode::W, KeyCode::D, KeyCode::S, KeyCode::A][(i / 90) % 4]);
+			if i % 300 == 0 {
+				input.keys_down.insert([KeyCode::Key1, KeyCode::Key2][i / 300 % 2]);
+			}
+			if i == 500 || i == 560 {
+				input.keys_down.insert(KeyCode::Escape);
+			}
+			if i % 200 < 150 {
+				input.mouse_buttons_down.insert(MouseButton::Left);
+			}
+			input.mouse_pos = Vec2::new((i as f32 * 0.05).sin() * 0.5 + 0.5, (i as f32 * 0.031).cos() * 0.5 + 0.5) * SCREEN_SIZE.as_vec2();
+			input.keys_pressed = input.keys_down.difference(&prev_keys_down).copied().collect();
+			input.mouse_buttons_pressed = input.mouse_buttons_down.difference(&prev_mouse_buttons_down).copied().collect();
+			prev_keys_down = input.keys_down.clone();
+			prev_mouse_buttons_down = input.mouse_buttons_down.clone();
+			input.resolve_actions(&input_bindings);
+			input
+		}).collect()
+	}
+	
+	fn assert_same_state(a: &PlayingData, b: &PlayingData) {
+		assert_eq!(a.tick_count, b.tick_count);
+		assert_eq!(a.score, b.score);
+		assert_eq!(a.kills, b.kills);
+		assert_eq!(a.player_pos, b.player_pos);
+		assert_eq!(a.player_vel, b.player_vel);
+		assert_eq!(a.player_health.to_bits(), b.player_health.to_bits());
+		assert_eq!(a.weapon_index, b.weapon_index);
+		assert_eq!(a.enemies.pos, b.enemies.pos);
+		assert_eq!(a.enemies.health, b.enemies.health);
+		assert_eq!(a.player_bullets.pos, b.player_bullets.pos);
+		assert_eq!(a.enemy_bullets.pos, b.enemy_bullets.pos);
+		assert_eq!(a.pickups.pos, b.pickups.pos);
+		assert_eq!(a.rng.clone().gen::<u64>(), b.rng.clone().gen::<u64>());
+	}
+	
+	#[test]
+	fn same_seed_and_inputs_give_the_same_run() {
+		let inputs = get_input_script();
+		let mut first = new_runner(12345);
+		let mut second = new_runner(12345);
+		run_script(&mut first, &inputs).unwrap();
+		run_script(&mut second, &inputs).unwrap();
+		assert_same_state(&first.playing_data, &second.playing_data);
+		// make sure the script actually exercised something
+		assert!(first.playing_data.tick_count > 300);
+		assert!(first.playing_data.kills > 0);
+		assert!(first.playing_data.recorded_tick_inputs.iter().any(|tick_input| tick_input.pause_toggled));
+		assert!(first.playing_data.recorded_tick_inputs.iter().any(|tick_input| tick_input.weapon_slot.is_some()));
+	}
+	
+	#[test]
+	fn run_script_stops_at_the_end_of_the_run() {
+		let mut runner = new_runner(12345);
+		runner.playing_data.player_health = 0.;
+		run_script(&mut runner, &get_input_script()).unwrap();
+		assert_eq!(runner.frame_count, 1);
+		assert_eq!(runner.playing_data.tick_count, 0);
+	}
+	
+	#[test]
+	fn saved_replay_plays_back_the_same_run() {
+		let mut recorded = new_runner(777);
+		run_script(&mut recorded, &get_input_script()).unwrap();
+		let replay = update_for_playing::get_session_replay(&recorded.playing_data);
+		let replay = Replay::from_bytes(&replay.to_bytes()).unwrap();
+		
+		// the seed comes from the replay, not from the runner
+		let mut played_back = new_runner(0);
+		let mut replay_data = ReplayData::empty();
+		update_for_replay::load_replay(&mut replay_data, &mut played_back.playing_data, replay).unwrap();
+		// playback stops wherever the player paused, so keep resuming it like a tester would
+		while replay_data.next_tick_index < replay_data.replay.tick_inputs.len() {
+			replay_data.is_paused = false;
+			update_for_replay::run_replay_ticks(&mut replay_data, &mut played_back.playing_data, played_back.frame_dt).unwrap();
+		}
+		assert_same_state(&recorded.playing_data, &played_back.playing_data);
+	}
+	
+	#[test]
+	fn replay_with_different_settings_is_rejected() {
+		let mut recorded = new_runner(777);
+		run_script(&mut recorded, &get_input_script()[..60]).unwrap();
+		let replay = update_for_playing::get_session_replay(&recorded.playing_data);
+		
+		let mut played_back = new_runner(777);
+		played_back.playing_data.loaded_settings.player_acceleration *= 2.;
+		let mut replay_data = ReplayData::empty();
+		assert!(update_for_replay::load_replay(&mut replay_data, &mut played_back.playing_data, replay).is_err());
+	}
+	
+	// fills the arena with thousands of bullets and times each tick against the tick budget
+	// run with `cargo test --release -- --ignored --nocapture collision_benchmark`
+	#[test]
+	#[ignore]
+	fn collision_benchmark() {
+		const ENEMY_COUNT: usize = 500;
+		const BULLET_COUNT: usize = 5000;
+		const TICK_COUNT: usize = 600;
+		
+		let mut runner = new_runner(0);
+		let input = InputSnapshot::empty(SCREEN_SIZE);
+		let weapon = runner.playing_data.weapons[runner.playing_data.weapon_index].clone();
+		let enemy_bullet_speed = runner.playing_data.enemy_archetypes[0].bullet_speed;
+		let mut total_time = Duration::ZERO;
+		let mut max_time = Duration::ZERO;
+		
+		while runner.playing_data.tick_count < TICK_COUNT {
+			let playing_data = &mut runner.playing_data;
+			
+			// keep the counts topped up, bullets leave the arena and enemies get killed
+			while playing_data.enemies.len() < ENEMY_COUNT {
+				let archetype_index = playing_data.enemies.len() % playing_data.enemy_archetypes.len();
+				let enemy = Enemy::new(archetype_index, &playing_data.enemy_archetypes[archetype_index], &mut playing_data.rng);
+				playing_data.enemies.push(enemy);
+			}
+			while playing_data.player_bullets.len() < BULLET_COUNT {
+				let (start, dest): ((f32, f32), (f32, f32)) = playing_data.rng.gen();
+				playing_data.player_bullets.push(Bullet::new(start.into(), dest.into(), weapon.bullet_speed, weapon.bullet_damage));
+			}
+			while playing_data.enemy_bullets.len() < BULLET_COUNT {
+				let (start, dest): ((f32, f32), (f32, f32)) = playing_data.rng.gen();
+				playing_data.enemy_bullets.push(Bullet::new(start.into(), dest.into(), enemy_bullet_speed, 0.));
+			}
+			playing_data.player_health = playing_data.player_max_health;
+			
+			// `frame_dt` is one tick, so every step runs exactly one tick
+			let start_time = Instant::now();
+			step(&mut runner, &input).unwrap();
+			let tick_time = start_time.elapsed();
+			total_time += tick_time;
+			max_time = max_time.max(tick_time);
+			
+		}
+		
+		let average_time = total_time / TICK_COUNT as u32;
+		let budget = Duration::from_secs_f32(program_settings::TICK_DURATION_SECS);
+		println!("Collision benchmark: {ENEMY_COUNT} enemies, {BULLET_COUNT} player bullets, {BULLET_COUNT} enemy bullets, {TICK_COUNT} ticks");
+		println!("Average tick: {average_time:?}, slowest tick: {max_time:?}, budget: {budget:?}");
+		assert!(average_time <= budget, "Average tick time {average_time:?} is over the budget of {budget:?}");
+	}
+	
+}
@@ -1,7 +1,17 @@
 use crate::{gui_integration_mod::init, prelude::*};
+use std::collections::HashSet;
 
 pub mod update_for_main_menu;
 pub mod update_for_playing;
+pub mod update_for_replay;
+pub mod update_for_game_over;
+pub mod update_for_name_entry;
+pub mod update_for_high_scores;
+pub mod update_for_options;
+pub mod headless;
+pub mod bullet_patterns;
+pub mod wave_scheduler;
+pub mod collision;
 
 
 
@@ -17,17 +27,41 @@ pub fn update_wrapper(app: &mut App, program_data: &mut ProgramData) {
 }
 
 pub fn update(app: &mut App, program_data: &mut ProgramData) -> Result<()> {
+	let input = get_input_snapshot(app, program_data.last_screen_size, &program_data.input_bindings);
 	
-	if app.keyboard.ctrl() && app.keyboard.was_pressed(KeyCode::R) {
+	if input.action_was_pressed(Action::Reload) {
 		println!("Reloading gui...");
 		init::init_all_guis(program_data)?;
+		println!("Reloading settings...");
+		let playing_data = &mut program_data.playing_data;
+		// a typo in the settings file shouldn't crash the game, so the old settings are kept instead
+		// the new settings are used starting with the next run
+		match load_program_settings(&get_program_settings_path(), &playing_data.loaded_enemy_archetypes, &playing_data.weapons) {
+			Result::Ok (settings) => playing_data.loaded_settings = settings,
+			Result::Err (err) => println!("Could not reload settings, keeping the old ones: {err:#}"),
+		}
+		println!("Reloading particle emitters...");
+		match load_particle_emitters(get_particle_emitters_path()) {
+			Result::Ok (particle_emitters) => playing_data.particle_emitters = particle_emitters,
+			Result::Err (err) => println!("Could not reload particle emitters, keeping the old ones: {err:#}"),
+		}
+		println!("Reloading input bindings...");
+		match load_input_bindings(&get_input_bindings_path()) {
+			Result::Ok (input_bindings) => program_data.input_bindings = input_bindings,
+			Result::Err (err) => println!("Could not reload input bindings, keeping the old ones: {err:#}"),
+		}
 		println!("Done");
 	}
 	
 	let dt = app.system_timer.delta_f32();
 	match &mut program_data.mode {
-		ProgramMode::MainMenu => update_for_main_menu::update(app, program_data, dt),
-		ProgramMode::Playing => update_for_playing::update(app, program_data, dt),
+		ProgramMode::MainMenu => update_for_main_menu::update(&input, program_data, dt),
+		ProgramMode::Playing => update_for_playing::update(&input, program_data, dt),
+		ProgramMode::Replay => update_for_replay::update(&input, program_data, dt),
+		ProgramMode::GameOver => update_for_game_over::update(&input, program_data, dt),
+		ProgramMode::NameEntry => update_for_name_entry::update(&input, program_data, dt),
+		ProgramMode::HighScores => update_for_high_scores::update(&input, program_data, dt),
+		ProgramMode::Options => update_for_options::update(&input, program_data, dt),
 	}?;
 	
 	if program_data.exit {
@@ -36,3 +70,23 @@ pub fn update(app: &mut App, program_data: &mut ProgramData) -> Result<()> {
 	
 	Ok(())
 }
+
+
+
+pub fn get_input_snapshot(app: &App, screen_size: UVec2, input_bindings: &InputBindings) -> InputSnapshot {
+	let mut output = InputSnapshot {
+		keys_down: app.keyboard.down.keys().copied().collect(),
+		keys_pressed: app.keyboard.pressed.clone(),
+		ctrl_is_down: app.keyboard.ctrl(),
+		mouse_pos: app.mouse.position().to_vec2(),
+		mouse_buttons_down: app.mouse.down.keys().copied().collect(),
+		mouse_buttons_pressed: app.mouse.pressed.clone(),
+		left_mouse_was_pressed: app.mouse.left_was_pressed(),
+		screen_size,
+		actions_down: HashSet::new(),
+		actions_pressed: HashSet::new(),
+		gui_keyboard_data: gui_integration_mod::get_gui_keyboard_data(&app.keyboard),
+	};
+	output.resolve_actions(input_bindings);
+	output
+}
@@ -0,0 +1,97 @@
+use crate::prelude::*;
+
+
+
+
+
+// easily keep track of control flow
+pub fn update(input: &InputSnapshot, program_data: &mut ProgramData, dt: f32) -> Result<()> {
+	process_gui_clicks(input, program_data)?;
+	gui::update::update_gui_elements(&mut program_data.game_over_data.gui, &input.gui_keyboard_data);
+	transfer_data_to_gui(&mut program_data.game_over_data)?;
+	process_inputs_after_main(input, program_data);
+	Ok(())
+}
+
+
+
+
+
+pub fn process_gui_clicks(input: &InputSnapshot, program_data: &mut ProgramData) -> Result<()> {
+	
+	let mouse_pos = input.mouse_pos.as_ivec2();
+	if input.left_mouse_was_pressed {
+		let hovered_elements = gui::utils::get_hovered_elements(&program_data.game_over_data.gui, (mouse_pos.x, mouse_pos.y), input.screen_size.to_tuple());
+		for element in hovered_elements {
+			if let Some(click_fn) = element.custom_data.click_fn {
+				let result = click_fn(program_data);
+				if let Err(err) = result {
+					panic!("Error while processing button press: {err}");
+				}
+				break;
+			}
+		}
+	}
+	
+	Ok(())
+}
+
+
+
+pub fn set_click_fns(gui: &mut GuiElement<CustomGuiData>) -> Result<()> {
+	
+	fn set_click_fn(element: &mut GuiElement<CustomGuiData>, click_fn: fn(&mut ProgramData) -> Result<()>) {
+		element.custom_data.click_fn = Some(click_fn);
+	}
+	const GUI_ERROR_MESSAGE: &str = "Could not add click function";
+	
+	fn retry_button(program_data: &mut ProgramData) -> Result<()> {
+		program_data.mode = ProgramMode::Playing;
+		program_data.playing_data.reset();
+		Ok(())
+	}
+	set_click_fn(gui.child_mut_or_message("retry_button", GUI_ERROR_MESSAGE)?, retry_button);
+	
+	fn main_menu_button(program_data: &mut ProgramData) -> Result<()> {
+		program_data.mode = ProgramMode::MainMenu;
+		program_data.main_menu_data.reset();
+		Ok(())
+	}
+	set_click_fn(gui.child_mut_or_message("main_menu_button", GUI_ERROR_MESSAGE)?, main_menu_button);
+	
+	Ok(())
+}
+
+
+
+
+
+pub fn transfer_data_to_gui(game_over_data: &mut GameOverData) -> Result<()> {
+	const GUI_ERROR_MESSAGE: &str = "Could not update gui";
+	let gui = &mut game_over_data.gui;
+	
+	let title_label = gui.child_mut_or_message("title_label", GUI_ERROR_MESSAGE)?;
+	title_label.text = vec!(String::from(if game_over_data.level_complete {"Level Complete!"} else {"Game Over"}));
+	let time_survived = game_over_data.time_survived.as_secs_f32();
+	let time_label = gui.child_mut_or_message("time_label", GUI_ERROR_MESSAGE)?;
+	time_label.text = vec!(format!("Time survived: {}:{:05.2}", (time_survived / 60.) as u32, time_survived % 60.));
+	let score_label = gui.child_mut_or_message("score_label", GUI_ERROR_MESSAGE)?;
+	score_label.text = vec!(format!("Score: {}", game_over_data.score));
+	let kills_label = gui.child_mut_or_message("kills_label", GUI_ERROR_MESSAGE)?;
+	kills_label.text = vec!(format!("Kills: {}", game_over_data.kills));
+	
+	Ok(())
+}
+
+
+
+
+
+pub fn process_inputs_after_main(input: &InputSnapshot, program_data: &mut ProgramData) {
+	
+	if input.action_was_pressed(Action::Back) {
+		program_data.mode = ProgramMode::MainMenu;
+		program_data.main_menu_data.reset();
+	}
+	
+}
@@ -0,0 +1,98 @@
+use crate::prelude::*;
+
+
+
+
+
+// easily keep track of control flow
+pub fn update(input: &InputSnapshot, program_data: &mut ProgramData, dt: f32) -> Result<()> {
+	process_gui_clicks(input, program_data)?;
+	gui::update::update_gui_elements(&mut program_data.high_scores_data.gui, &input.gui_keyboard_data);
+	transfer_data_to_gui(&mut program_data.high_scores_data, &program_data.high_scores)?;
+	process_inputs_after_main(input, program_data);
+	Ok(())
+}
+
+
+
+
+
+pub fn process_gui_clicks(input: &InputSnapshot, program_data: &mut ProgramData) -> Result<()> {
+	
+	let mouse_pos = input.mouse_pos.as_ivec2();
+	if input.left_mouse_was_pressed {
+		let hovered_elements = gui::utils::get_hovered_elements(&program_data.high_scores_data.gui, (mouse_pos.x, mouse_pos.y), input.screen_size.to_tuple());
+		for element in hovered_elements {
+			if let Some(click_fn) = element.custom_data.click_fn {
+				let result = click_fn(program_data);
+				if let Err(err) = result {
+					panic!("Error while processing button press: {err}");
+				}
+				break;
+			}
+		}
+	}
+	
+	Ok(())
+}
+
+
+
+pub fn set_click_fns(gui: &mut GuiElement<CustomGuiData>) -> Result<()> {
+	
+	fn set_click_fn(element: &mut GuiElement<CustomGuiData>, click_fn: fn(&mut ProgramData) -> Result<()>) {
+		element.custom_data.click_fn = Some(click_fn);
+	}
+	const GUI_ERROR_MESSAGE: &str = "Could not add click function";
+	
+	fn back_button(program_data: &mut ProgramData) -> Result<()> {
+		program_data.mode = ProgramMode::MainMenu;
+		Ok(())
+	}
+	set_click_fn(gui.child_mut_or_message("back_button", GUI_ERROR_MESSAGE)?, back_button);
+	
+	Ok(())
+}
+
+
+
+
+
+pub fn transfer_data_to_gui(high_scores_data: &mut HighScoresData, high_scores: &[HighScoreEntry]) -> Result<()> {
+	const GUI_ERROR_MESSAGE: &str = "Could not update gui";
+	let gui = &mut high_scores_data.gui;
+	
+	let list_label = gui.child_mut_or_message("list_label", GUI_ERROR_MESSAGE)?;
+	list_label.text.clear();
+	for (i, entry) in high_scores.iter().enumerate() {
+		let duration = entry.duration.as_secs();
+		list_label.text.push(format!(
+			"{}. {}  -  {}  ({}, {}:{:02}, {}, seed {})",
+			i + 1,
+			entry.name,
+			entry.score,
+			entry.difficulty.get_name(),
+			duration / 60,
+			duration % 60,
+			entry.date,
+			entry.seed,
+		));
+	}
+	if list_label.text.is_empty() {
+		list_label.text.push(String::from("No high scores yet"));
+	}
+	
+	Ok(())
+}
+
+
+
+
+
+pub fn process_inputs_after_main(input: &InputSnapshot, program_data: &mut ProgramData) {
+	
+	if input.action_was_pressed(Action::Back) {
+		program_data.mode = ProgramMode::MainMenu;
+	}
+	
+}
@@ -5,13 +5,12 @@ use crate::prelude::*;
 
 
 // easily keep track of control flow
-pub fn update(app: &mut App, program_data: &mut ProgramData, dt: f32) -> Result<()> {
-	process_inputs_before_main(app, program_data);
-	process_gui_clicks(app, program_data)?;
-	let keyboard_data = gui_integration_mod::get_gui_keyboard_data(&app.keyboard);
-	gui::update::update_gui_elements(&mut program_data.playing_data.gui, &keyboard_data);
-	transfer_data_to_gui(&mut program_data.main_menu_data)?;
-	process_inputs_after_main(app, program_data);
+pub fn update(input: &InputSnapshot, program_data: &mut ProgramData, dt: f32) -> Result<()> {
+	process_inputs_before_main(input, program_data);
+	process_gui_clicks(input, program_data)?;
+	gui::update::update_gui_elements(&mut program_data.main_menu_data.gui, &input.gui_keyboard_data);
+	transfer_data_to_gui(&mut program_data.main_menu_data, program_data.playing_data.difficulty)?;
+	process_inputs_after_main(input, program_data);
 	Ok(())
 }
 
@@ -19,25 +18,32 @@ pub fn update(app: &mut App, program_data: &mut ProgramData, dt: f32) -> Result<
 
 
 
-pub fn process_inputs_before_main(app: &mut App, program_data: &mut ProgramData) {
+pub fn process_inputs_before_main(input: &InputSnapshot, program_data: &mut ProgramData) {
 	
-	if app.keyboard.was_pressed(KeyCode::Escape) {
+	if input.action_was_pressed(Action::Back) {
 		program_data.exit = true;
 		return;
 	}
 	
+	let difficulty = &mut program_data.playing_data.difficulty;
+	if input.action_was_pressed(Action::MenuLeft) {
+		*difficulty = difficulty.get_offset(-1);
+	}
+	if input.action_was_pressed(Action::MenuRight) {
+		*difficulty = difficulty.get_offset(1);
+	}
+	
 }
 
 
 
 
 
-pub fn process_gui_clicks(app: &mut App, program_data: &mut ProgramData) -> Result<()> {
+pub fn process_gui_clicks(input: &InputSnapshot, program_data: &mut ProgramData) -> Result<()> {
 	
-	let mouse_pos = app.mouse.position().to_i32();
-	let last_screen_size = program_data.last_screen_size;
-	if app.mouse.left_was_pressed() {
-		let hovered_elements = gui::utils::get_hovered_elements(&program_data.main_menu_data.gui, mouse_pos, last_screen_size.to_tuple());
+	let mouse_pos = input.mouse_pos.as_ivec2();
+	if input.left_mouse_was_pressed {
+		let hovered_elements = gui::utils::get_hovered_elements(&program_data.main_menu_data.gui, (mouse_pos.x, mouse_pos.y), input.screen_size.to_tuple());
 		for element in hovered_elements {
 			if let Some(click_fn) = element.custom_data.click_fn {
 				let result = click_fn(program_data);
@@ -70,6 +76,25 @@ pub fn set_click_fns(gui: &mut GuiElement<CustomGuiData>) -> Result<()> {
 	}
 	set_click_fn(gui.child_mut_or_message("play_button", GUI_ERROR_MESSAGE)?, play_button);
 	
+	fn difficulty_button(program_data: &mut ProgramData) -> Result<()> {
+		let difficulty = &mut program_data.playing_data.difficulty;
+		*difficulty = difficulty.get_offset(1);
+		Ok(())
+	}
+	set_click_fn(gui.child_mut_or_message("difficulty_button", GUI_ERROR_MESSAGE)?, difficulty_button);
+	
+	fn high_scores_button(program_data: &mut ProgramData) -> Result<()> {
+		program_data.mode = ProgramMode::HighScores;
+		Ok(())
+	}
+	set_click_fn(gui.child_mut_or_message("high_scores_button", GUI_ERROR_MESSAGE)?, high_scores_button);
+	
+	fn options_button(program_data: &mut ProgramData) -> Result<()> {
+		update_for_options::open_options(program_data, false);
+		Ok(())
+	}
+	set_click_fn(gui.child_mut_or_message("options_button", GUI_ERROR_MESSAGE)?, options_button);
+	
 	fn exit_button(program_data: &mut ProgramData) -> Result<()> {
 		program_data.exit = true;
 		Ok(())
@@ -83,7 +108,7 @@ pub fn set_click_fns(gui: &mut GuiElement<CustomGuiData>) -> Result<()> {
 
 
 
-pub fn transfer_data_to_gui(main_menu_data: &mut MainMenuData) -> Result<()> {
+pub fn transfer_data_to_gui(main_menu_data: &mut MainMenuData, difficulty: Difficulty) -> Result<()> {
 	const GUI_ERROR_MESSAGE: &str = "Could not update gui";
 	let gui = &mut main_menu_data.gui;
 	
@@ -94,6 +119,9 @@ pub fn transfer_data_to_gui(main_menu_data: &mut MainMenuData) -> Result<()> {
 	play_button_progress.width = in_menu_duration.as_secs_f32() / program_settings::MAIN_MENU_WAIT_DURATION.as_secs_f32();
 	play_button_progress.width = play_button_progress.width.min(1.);
 	
+	let difficulty_button = gui.child_mut_or_message("difficulty_button", GUI_ERROR_MESSAGE)?;
+	difficulty_button.text = vec!(format!("Difficulty: {}", difficulty.get_name()));
+	
 	Ok(())
 }
 
@@ -101,11 +129,11 @@ pub fn transfer_data_to_gui(main_menu_data: &mut MainMenuData) -> Result<()> {
 
 
 
-pub fn process_inputs_after_main(app: &mut App, program_data: &mut ProgramData) {
+pub fn process_inputs_after_main(input: &InputSnapshot, program_data: &mut ProgramData) {
 	
 	let wait_duration_ended = program_data.main_menu_data.enter_time.elapsed() > program_settings::MAIN_MENU_WAIT_DURATION;
 	
-	if app.keyboard.was_pressed(KeyCode::Space) && wait_duration_ended {
+	if input.action_was_pressed(Action::Confirm) && wait_duration_ended {
 		program_data.mode = ProgramMode::Playing;
 		program_data.playing_data.reset();
 		return;
@@ -0,0 +1,137 @@
+use crate::prelude::*;
+
+
+
+
+
+// easily keep track of control flow
+pub fn update(input: &InputSnapshot, program_data: &mut ProgramData, dt: f32) -> Result<()> {
+	process_gui_clicks(input, program_data)?;
+	gui::update::update_gui_elements(&mut program_data.name_entry_data.gui, &input.gui_keyboard_data);
+	transfer_data_to_gui(&mut program_data.name_entry_data)?;
+	process_inputs_after_main(input, program_data)?;
+	Ok(())
+}
+
+
+
+
+
+pub fn start_name_entry(program_data: &mut ProgramData, entry: HighScoreEntry) {
+	let name_entry_data = &mut program_data.name_entry_data;
+	name_entry_data.pending_entry = entry;
+	if let Some(name_field) = name_entry_data.gui.child_mut_option("name_field") {
+		name_field.text = vec!(String::new());
+		name_field.cursor_x = 0;
+		name_field.cursor_y = 0;
+		name_field.cursor_target_x = 0;
+		name_field.is_editing_text = true;
+	}
+	program_data.mode = ProgramMode::NameEntry;
+}
+
+
+
+pub fn submit_name(program_data: &mut ProgramData) -> Result<()> {
+	const GUI_ERROR_MESSAGE: &str = "Could not read entered name";
+	let name_entry_data = &mut program_data.name_entry_data;
+	let name_field = name_entry_data.gui.child_or_message("name_field", GUI_ERROR_MESSAGE)?;
+	let mut entry = name_entry_data.pending_entry.clone();
+	entry.name = sanitize_high_score_name(&name_field.text.join(" "));
+	
+	let high_scores = &mut program_data.high_scores;
+	if let Some(insert_index) = get_high_score_insert_index(high_scores, entry.score) {
+		high_scores.insert(insert_index, entry);
+		high_scores.truncate(program_settings::HIGH_SCORE_COUNT);
+		if let Err(err) = save_high_scores(get_high_scores_path(), high_scores) {
+			println!("Warning: could not save high scores: {err:?}");
+		}
+	}
+	
+	program_data.mode = ProgramMode::GameOver;
+	Ok(())
+}
+
+
+
+
+
+pub fn process_gui_clicks(input: &InputSnapshot, program_data: &mut ProgramData) -> Result<()> {
+	
+	let mouse_pos = input.mouse_pos.as_ivec2();
+	if input.left_mouse_was_pressed {
+		let hovered_elements = gui::utils::get_hovered_elements(&program_data.name_entry_data.gui, (mouse_pos.x, mouse_pos.y), input.screen_size.to_tuple());
+		for element in hovered_elements {
+			if let Some(click_fn) = element.custom_data.click_fn {
+				let result = click_fn(program_data);
+				if let Err(err) = result {
+					panic!("Error while processing button press: {err}");
+				}
+				break;
+			}
+		}
+	}
+	
+	Ok(())
+}
+
+
+
+pub fn set_click_fns(gui: &mut GuiElement<CustomGuiData>) -> Result<()> {
+	
+	fn set_click_fn(element: &mut GuiElement<CustomGuiData>, click_fn: fn(&mut ProgramData) -> Result<()>) {
+		element.custom_data.click_fn = Some(click_fn);
+	}
+	const GUI_ERROR_MESSAGE: &str = "Could not add click function";
+	
+	fn name_field(program_data: &mut ProgramData) -> Result<()> {
+		let name_field = program_data.name_entry_data.gui.child_mut_or_message("name_field", "Could not find name field")?;
+		name_field.is_editing_text = true;
+		Ok(())
+	}
+	set_click_fn(gui.child_mut_or_message("name_field", GUI_ERROR_MESSAGE)?, name_field);
+	
+	set_click_fn(gui.child_mut_or_message("submit_button", GUI_ERROR_MESSAGE)?, submit_name);
+	
+	fn skip_button(program_data: &mut ProgramData) -> Result<()> {
+		program_data.mode = ProgramMode::GameOver;
+		Ok(())
+	}
+	set_click_fn(gui.child_mut_or_message("skip_button", GUI_ERROR_MESSAGE)?, skip_button);
+	
+	Ok(())
+}
+
+
+
+
+
+pub fn transfer_data_to_gui(name_entry_data: &mut NameEntryData) -> Result<()> {
+	const GUI_ERROR_MESSAGE: &str = "Could not update gui";
+	let gui = &mut name_entry_data.gui;
+	
+	let score_label = gui.child_mut_or_message("score_label", GUI_ERROR_MESSAGE)?;
+	score_label.text = vec!(format!("Score: {}", name_entry_data.pending_entry.score));
+	
+	Ok(())
+}
+
+
+
+
+
+pub fn process_inputs_after_main(input: &InputSnapshot, program_data: &mut ProgramData) -> Result<()> {
+	
+	// return finishes editing the name field (see `process_typing()`), which also submits it
+	let name_field_was_editing = program_data.name_entry_data.gui.child_or_message("name_field", "Could not read name field")?.is_editing_text;
+	if !name_field_was_editing && input.action_was_pressed(Action::Confirm) {
+		submit_name(program_data)?;
+		return Ok(());
+	}
+	
+	if input.action_was_pressed(Action::Back) {
+		program_data.mode = ProgramMode::GameOver;
+	}
+	
+	Ok(())
+}
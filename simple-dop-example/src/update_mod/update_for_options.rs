@@ -0,0 +1,267 @@
+use crate::prelude::*;
+
+
+
+// these have to match the `row_<n>/binding_<n>` elements in `assets/gui/options`
+pub const ROWS_PER_PAGE: usize = 8;
+pub const BINDING_SLOTS_PER_ROW: usize = 2;
+
+
+
+// easily keep track of control flow
+pub fn update(input: &InputSnapshot, program_data: &mut ProgramData, dt: f32) -> Result<()> {
+	// while waiting for a new binding, every key and click that isn't on a button goes to that instead, including escape
+	if program_data.options_data.rebinding.is_some() {
+		let button_was_clicked = process_gui_clicks(input, program_data)?;
+		if !button_was_clicked {
+			process_rebinding_inputs(input, program_data);
+		}
+	} else {
+		process_gui_clicks(input, program_data)?;
+		process_inputs_after_main(input, program_data);
+	}
+	gui::update::update_gui_elements(&mut program_data.options_data.gui, &input.gui_keyboard_data);
+	transfer_data_to_gui(&mut program_data.options_data, &program_data.input_bindings)?;
+	Ok(())
+}
+
+
+
+pub fn open_options(program_data: &mut ProgramData, return_to_playing: bool) {
+	program_data.options_data.reset(return_to_playing);
+	program_data.mode = ProgramMode::Options;
+}
+
+pub fn close_options(program_data: &mut ProgramData) {
+	if program_data.options_data.return_to_playing {
+		program_data.mode = ProgramMode::Playing;
+	} else {
+		program_data.mode = ProgramMode::MainMenu;
+		program_data.main_menu_data.reset();
+	}
+}
+
+
+
+
+
+pub fn process_rebinding_inputs(input: &InputSnapshot, program_data: &mut ProgramData) {
+	let options_data = &mut program_data.options_data;
+	let Some((action, slot)) = options_data.rebinding else {return;};
+	let Some(binding) = get_pressed_binding(input) else {return;};
+	options_data.rebinding = None;
+	let conflicting_actions = program_data.input_bindings.set_binding(action, slot, binding);
+	options_data.status = if conflicting_actions.is_empty() {
+		format!("Bound {} to \"{}\"", binding.get_name(), action.get_name())
+	} else {
+		let names = conflicting_actions.iter().map(|action| format!("\"{}\"", action.get_name())).collect::<Vec<_>>();
+		format!("Bound {} to \"{}\" (removed it from {})", binding.get_name(), action.get_name(), names.join(", "))
+	};
+	save_bindings(program_data);
+	
+}
+
+
+
+// the key or mouse button that was pressed this frame, holding ctrl while pressing a key gives a ctrl binding
+// (ctrl on its own is skipped so that it can be held first)
+pub fn get_pressed_binding(input: &InputSnapshot) -> Option<Binding> {
+	let key = BINDABLE_KEYS.iter().copied()
+		.filter(|key| !matches!(key, KeyCode::LControl | KeyCode::RControl))
+		.find(|key| input.key_was_pressed(*key));
+	if let Some(key) = key {
+		return Some(if input.ctrl_is_down {Binding::CtrlKey (key)} else {Binding::Key (key)});
+	}
+	BINDABLE_MOUSE_BUTTONS.iter().copied()
+		.find(|button| input.mouse_buttons_pressed.contains(button))
+		.map(Binding::Mouse)
+}
+
+
+
+pub fn save_bindings(program_data: &mut ProgramData) {
+	if let Err(err) = save_input_bindings(&get_input_bindings_path(), &program_data.input_bindings) {
+		program_data.options_data.status = format!("Could not save bindings: {err}");
+	}
+}
+
+
+
+
+
+// returns whether a button was clicked
+pub fn process_gui_clicks(input: &InputSnapshot, program_data: &mut ProgramData) -> Result<bool> {
+	
+	let mouse_pos = input.mouse_pos.as_ivec2();
+	if input.left_mouse_was_pressed {
+		let hovered_elements = gui::utils::get_hovered_elements(&program_data.options_data.gui, (mouse_pos.x, mouse_pos.y), input.screen_size.to_tuple());
+		for element in hovered_elements {
+			if let Some(click_fn) = element.custom_data.click_fn {
+				let result = click_fn(program_data);
+				if let Err(err) = result {
+					panic!("Error while processing button press: {err}");
+				}
+				return Ok(true);
+			}
+		}
+	}
+	
+	Ok(false)
+}
+
+
+
+pub fn set_click_fns(gui: &mut GuiElement<CustomGuiData>) -> Result<()> {
+	
+	fn set_click_fn(element: &mut GuiElement<CustomGuiData>, click_fn: fn(&mut ProgramData) -> Result<()>) {
+		element.custom_data.click_fn = Some(click_fn);
+	}
+	const GUI_ERROR_MESSAGE: &str = "Could not add click function";
+	
+	// click fns can't capture anything, so every button gets its own copy of this
+	// clicking the binding that's already waiting cancels it
+	fn binding_button<const ROW: usize, const SLOT: usize>(program_data: &mut ProgramData) -> Result<()> {
+		let options_data = &mut program_data.options_data;
+		let Some(action) = Action::ALL.get(options_data.page * ROWS_PER_PAGE + ROW) else {return Ok(());};
+		if options_data.rebinding == Some((*action, SLOT)) {
+			cancel_rebinding(options_data);
+			return Ok(());
+		}
+		options_data.rebinding = Some((*action, SLOT));
+		options_data.status = format!("Press a key or mouse button for \"{}\" (click it again or click back to cancel)", action.get_name());
+		Ok(())
+	}
+	type ClickFn = fn(&mut ProgramData) -> Result<()>;
+	const BINDING_BUTTONS: [[ClickFn; BINDING_SLOTS_PER_ROW]; ROWS_PER_PAGE] = [
+		[binding_button::<0, 0>, binding_button::<0, 1>],
+		[binding_button::<1, 0>, binding_button::<1, 1>],
+		[binding_button::<2, 0>, binding_button::<2, 1>],
+		[binding_button::<3, 0>, binding_button::<3, 1>],
+		[binding_button::<4, 0>, binding_button::<4, 1>],
+		[binding_button::<5, 0>, binding_button::<5, 1>],
+		[binding_button::<6, 0>, binding_button::<6, 1>],
+		[binding_button::<7, 0>, binding_button::<7, 1>],
+	];
+	for (row, click_fns) in BINDING_BUTTONS.into_iter().enumerate() {
+		for (slot, click_fn) in click_fns.into_iter().enumerate() {
+			set_click_fn(gui.child_mut_or_message(&format!("row_{row}/binding_{slot}"), GUI_ERROR_MESSAGE)?, click_fn);
+		}
+	}
+	
+	fn prev_page_button(program_data: &mut ProgramData) -> Result<()> {
+		let options_data = &mut program_data.options_data;
+		options_data.page = options_data.page.saturating_sub(1);
+		Ok(())
+	}
+	set_click_fn(gui.child_mut_or_message("prev_page_button", GUI_ERROR_MESSAGE)?, prev_page_button);
+	
+	fn next_page_button(program_data: &mut ProgramData) -> Result<()> {
+		let options_data = &mut program_data.options_data;
+		options_data.page = (options_data.page + 1).min(get_page_count() - 1);
+		Ok(())
+	}
+	set_click_fn(gui.child_mut_or_message("next_page_button", GUI_ERROR_MESSAGE)?, next_page_button);
+	
+	fn defaults_button(program_data: &mut ProgramData) -> Result<()> {
+		program_data.input_bindings = InputBindings::new();
+		program_data.options_data.status = String::from("Reset every binding to its default");
+		save_bindings(program_data);
+		Ok(())
+	}
+	set_click_fn(gui.child_mut_or_message("defaults_button", GUI_ERROR_MESSAGE)?, defaults_button);
+	
+	// only shown while waiting for a new binding
+	fn unbind_button(program_data: &mut ProgramData) -> Result<()> {
+		let options_data = &mut program_data.options_data;
+		let Some((action, slot)) = options_data.rebinding.take() else {return Ok(());};
+		options_data.status = match program_data.input_bindings.remove_binding(action, slot) {
+			Some(binding) => format!("Unbound {} from \"{}\"", binding.get_name(), action.get_name()),
+			None => String::from("Nothing to unbind"),
+		};
+		save_bindings(program_data);
+		Ok(())
+	}
+	set_click_fn(gui.child_mut_or_message("unbind_button", GUI_ERROR_MESSAGE)?, unbind_button);
+	
+	fn back_button(program_data: &mut ProgramData) -> Result<()> {
+		if program_data.options_data.rebinding.is_some() {
+			cancel_rebinding(&mut program_data.options_data);
+			return Ok(());
+		}
+		close_options(program_data);
+		Ok(())
+	}
+	set_click_fn(gui.child_mut_or_message("back_button", GUI_ERROR_MESSAGE)?, back_button);
+	
+	Ok(())
+}
+
+
+
+pub fn cancel_rebinding(options_data: &mut OptionsData) {
+	options_data.rebinding = None;
+	options_data.status = String::from("Cancelled");
+}
+
+
+
+pub fn get_page_count() -> usize {
+	Action::ALL.len().div_ceil(ROWS_PER_PAGE)
+}
+
+
+
+
+
+pub fn transfer_data_to_gui(options_data: &mut OptionsData, input_bindings: &InputBindings) -> Result<()> {
+	const GUI_ERROR_MESSAGE: &str = "Could not update gui";
+	let gui = &mut options_data.gui;
+	
+	for row_index in 0..ROWS_PER_PAGE {
+		let row = gui.child_mut_or_message(&format!("row_{row_index}"), GUI_ERROR_MESSAGE)?;
+		let action = Action::ALL.get(options_data.page * ROWS_PER_PAGE + row_index).copied();
+		row.visible = action.is_some();
+		row.enabled = action.is_some();
+		let Some(action) = action else {continue;};
+		
+		let action_label = row.child_mut_or_message("action_label", GUI_ERROR_MESSAGE)?;
+		action_label.text = vec!(action.get_name().to_string());
+		let bindings = input_bindings.get(action);
+		for slot in 0..BINDING_SLOTS_PER_ROW {
+			let binding_button = row.child_mut_or_message(&format!("binding_{slot}"), GUI_ERROR_MESSAGE)?;
+			let text = if options_data.rebinding == Some((action, slot)) {
+				String::from("...")
+			} else {
+				bindings.get(slot).map(Binding::get_name).unwrap_or_else(|| String::from("-"))
+			};
+			binding_button.text = vec!(text);
+		}
+	}
+	
+	let is_rebinding = options_data.rebinding.is_some();
+	let defaults_button = gui.child_mut_or_message("defaults_button", GUI_ERROR_MESSAGE)?;
+	defaults_button.visible = !is_rebinding;
+	defaults_button.enabled = !is_rebinding;
+	let unbind_button = gui.child_mut_or_message("unbind_button", GUI_ERROR_MESSAGE)?;
+	unbind_button.visible = is_rebinding;
+	unbind_button.enabled = is_rebinding;
+	
+	let page_label = gui.child_mut_or_message("page_label", GUI_ERROR_MESSAGE)?;
+	page_label.text = vec!(format!("{} / {}", options_data.page + 1, get_page_count()));
+	let status_label = gui.child_mut_or_message("status_label", GUI_ERROR_MESSAGE)?;
+	status_label.text = vec!(options_data.status.clone());
+	
+	Ok(())
+}
+
+
+
+
+
+pub fn process_inputs_after_main(input: &InputSnapshot, program_data: &mut ProgramData) {
+	
+	if input.action_was_pressed(Action::Back) {
+		close_options(program_data);
+	}
+	
+}
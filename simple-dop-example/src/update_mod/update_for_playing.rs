@@ -1,42 +1,66 @@
 use crate::prelude::*;
+use notan::random::rand::Rng;
 
 
 
 
 
 // easily keep track of control flow
-pub fn update(app: &mut App, program_data: &mut ProgramData, dt: f32) -> Result<()> {
-	process_inputs_before_main(app, program_data, dt);
-	process_gui_clicks(app, program_data)?;
-	if !program_data.playing_data.pause_data.is_paused {
-		update_game(&mut program_data.playing_data, dt)?;
-	}
-	let keyboard_data = gui_integration_mod::get_gui_keyboard_data(&app.keyboard);
-	gui::update::update_gui_elements(&mut program_data.playing_data.gui, &keyboard_data);
-	update_pause_menu(&mut program_data.playing_data.pause_data, dt);
-	transfer_data_to_gui(app, &mut program_data.playing_data)?;
-	process_inputs_after_main(app, program_data);
+pub fn update(input: &InputSnapshot, program_data: &mut ProgramData, dt: f32) -> Result<()> {
+	process_inputs_before_main(input, &mut program_data.playing_data);
+	process_gui_clicks(input, program_data)?;
+	let run_is_over = update_simulation(input, &mut program_data.playing_data, dt)?;
+	if run_is_over {
+		end_run(program_data);
+		return Ok(());
+	}
+	gui::update::update_gui_elements(&mut program_data.playing_data.gui, &input.gui_keyboard_data);
+	transfer_data_to_gui(&mut program_data.playing_data)?;
 	Ok(())
 }
 
 
 
+// everything in `update()` that doesn't need the gui, also used by `headless::step()`
+// returns whether the run is over, in which case the rest of the frame is skipped
+pub fn update_simulation(input: &InputSnapshot, playing_data: &mut PlayingData, dt: f32) -> Result<bool> {
+	if !playing_data.pause_data.is_paused {
+		run_game_ticks(playing_data, dt)?;
+	}
+	if playing_data.is_run_over() {
+		return Ok(true);
+	}
+	update_pause_menu(&mut playing_data.pause_data, dt);
+	process_inputs_after_main(input, playing_data);
+	Ok(false)
+}
+
+
 
 
-pub fn process_inputs_before_main(app: &mut App, program_data: &mut ProgramData, dt: f32) {
+
+pub fn process_inputs_before_main(input: &InputSnapshot, playing_data: &mut PlayingData) {
 	
-	let playing_data = &mut program_data.playing_data;
-	if app.keyboard.is_down(KeyCode::W) || app.keyboard.is_down(KeyCode::Up) {
-		playing_data.player_vel.y -= program_settings::PLAYER_ACCELERATION * dt;
-	}
-	if app.keyboard.is_down(KeyCode::S) || app.keyboard.is_down(KeyCode::Down) {
-		playing_data.player_vel.y += program_settings::PLAYER_ACCELERATION * dt;
-	}
-	if app.keyboard.is_down(KeyCode::A) || app.keyboard.is_down(KeyCode::Left) {
-		playing_data.player_vel.x -= program_settings::PLAYER_ACCELERATION * dt;
+	let aim_target = arena_coords::get_aim_target(playing_data, input.mouse_pos, input.screen_size);
+	
+	// movement is only recorded here, it's applied once per tick in `update_game()`
+	let tick_input = &mut playing_data.pending_tick_input;
+	tick_input.move_up    = input.action_is_down(Action::MoveUp);
+	tick_input.move_down  = input.action_is_down(Action::MoveDown);
+	tick_input.move_left  = input.action_is_down(Action::MoveLeft);
+	tick_input.move_right = input.action_is_down(Action::MoveRight);
+	
+	// firing is latched until a tick sees it, so that a quick click between two ticks still fires
+	if playing_data.pause_data.is_paused {return;}
+	playing_data.fire_button_is_down = input.action_is_down(Action::Fire);
+	if input.action_is_down(Action::Fire) || input.action_was_pressed(Action::Fire) {
+		tick_input.fire_held = true;
 	}
-	if app.keyboard.is_down(KeyCode::D) || app.keyboard.is_down(KeyCode::Right) {
-		playing_data.player_vel.x += program_settings::PLAYER_ACCELERATION * dt;
+	tick_input.aim_target = aim_target;
+	for (i, action) in Action::WEAPON_SLOTS.into_iter().enumerate() {
+		if input.action_was_pressed(action) {
+			tick_input.weapon_slot = Some(i as u8 + 1);
+		}
 	}
 	
 }
@@ -45,12 +69,11 @@ pub fn process_inputs_before_main(app: &mut App, program_data: &mut ProgramData,
 
 
 
-pub fn process_gui_clicks(app: &mut App, program_data: &mut ProgramData) -> Result<()> {
+pub fn process_gui_clicks(input: &InputSnapshot, program_data: &mut ProgramData) -> Result<()> {
 	
-	let mouse_pos = app.mouse.position().to_i32();
-	let last_screen_size = program_data.last_screen_size;
-	if app.mouse.left_was_pressed() {
-		let hovered_elements = gui::utils::get_hovered_elements(&program_data.playing_data.gui, mouse_pos, last_screen_size.to_tuple());
+	let mouse_pos = input.mouse_pos.as_ivec2();
+	if input.left_mouse_was_pressed {
+		let hovered_elements = gui::utils::get_hovered_elements(&program_data.playing_data.gui, (mouse_pos.x, mouse_pos.y), input.screen_size.to_tuple());
 		for element in hovered_elements {
 			if let Some(click_fn) = element.custom_data.click_fn {
 				let result = click_fn(program_data);
@@ -77,12 +100,23 @@ pub fn set_click_fns(gui: &mut GuiElement<CustomGuiData>) -> Result<()> {
 	let main_pause_menu = gui.child_mut_or_message("main_pause_menu", GUI_ERROR_MESSAGE)?; {
 		
 		fn resume_button(program_data: &mut ProgramData) -> Result<()> {
-			program_data.playing_data.pause_data.is_paused = false;
+			let playing_data = &mut program_data.playing_data;
+			if !playing_data.pause_data.is_paused {return Ok(());}
+			playing_data.pause_data.is_paused = false;
+			playing_data.pending_tick_input.pause_toggled = true;
 			Ok(())
 		}
 		set_click_fn(main_pause_menu.child_mut_or_message("resume_button", GUI_ERROR_MESSAGE)?, resume_button);
 		
+		fn options_button(program_data: &mut ProgramData) -> Result<()> {
+			if !program_data.playing_data.pause_data.is_paused {return Ok(());}
+			update_for_options::open_options(program_data, true);
+			Ok(())
+		}
+		set_click_fn(main_pause_menu.child_mut_or_message("options_button", GUI_ERROR_MESSAGE)?, options_button);
+		
 		fn exit_button(program_data: &mut ProgramData) -> Result<()> {
+			save_session_replay(&program_data.playing_data);
 			program_data.mode = ProgramMode::MainMenu;
 			program_data.main_menu_data.reset();
 			Ok(())
@@ -98,69 +132,255 @@ pub fn set_click_fns(gui: &mut GuiElement<CustomGuiData>) -> Result<()> {
 
 
 
-pub fn update_game(playing_data: &mut PlayingData, dt: f32) -> Result<()> {
+// steps the simulation in fixed-size ticks so that it plays out the same no matter the framerate
+pub fn run_game_ticks(playing_data: &mut PlayingData, dt: f32) -> Result<()> {
+	playing_data.tick_accumulator += dt;
+	let mut ticks_this_frame = 0;
+	while playing_data.tick_accumulator >= program_settings::TICK_DURATION_SECS {
+		if playing_data.is_run_over() {break;}
+		playing_data.tick_accumulator -= program_settings::TICK_DURATION_SECS;
+		let tick_input = playing_data.pending_tick_input.clone();
+		playing_data.pending_tick_input.fire_held = playing_data.fire_button_is_down;
+		playing_data.pending_tick_input.weapon_slot = None;
+		playing_data.pending_tick_input.pause_toggled = false;
+		update_game(playing_data, &tick_input, program_settings::TICK_DURATION_SECS)?;
+		playing_data.recorded_tick_inputs.push(tick_input);
+		ticks_this_frame += 1;
+		// drop the backlog after a long stall instead of trying to catch up all at once
+		if ticks_this_frame >= program_settings::MAX_TICKS_PER_FRAME {
+			playing_data.tick_accumulator = playing_data.tick_accumulator.min(program_settings::TICK_DURATION_SECS);
+			break;
+		}
+	}
+	Ok(())
+}
+
+
+
+// advances the game by exactly one tick (`dt` should always be `TICK_DURATION_SECS`)
+pub fn update_game(playing_data: &mut PlayingData, tick_input: &TickInput, dt: f32) -> Result<()> {
+	
+	
+	
+	playing_data.tick_count += 1;
+	
+	// save positions for render interpolation
+	playing_data.prev_player_pos = playing_data.player_pos;
+	playing_data.enemies.prev_pos.copy_from_slice(&playing_data.enemies.pos);
+	playing_data.player_bullets.prev_pos.copy_from_slice(&playing_data.player_bullets.pos);
+	playing_data.enemy_bullets.prev_pos.copy_from_slice(&playing_data.enemy_bullets.pos);
+	playing_data.pickups.prev_pos.copy_from_slice(&playing_data.pickups.pos);
 	
 	
 	
 	// update player movement
+	playing_data.player_vel += tick_input.get_move_dir() * playing_data.settings.player_acceleration * dt;
 	playing_data.player_pos += playing_data.player_vel * dt;
-	playing_data.player_vel *= program_settings::PLAYER_DRAG_COEF.powf(dt);
-	let player_size_vec2 = Vec2::new(program_settings::PLAYER_SIZE, program_settings::PLAYER_SIZE);
+	playing_data.player_vel *= playing_data.settings.player_drag_coef.powf(dt);
+	let player_size_vec2 = Vec2::new(playing_data.settings.player_size, playing_data.settings.player_size);
 	playing_data.player_pos = playing_data.player_pos.clamp(player_size_vec2 * 0.5, 1.0 - player_size_vec2 * 0.5);
 	
 	
 	
+	// switch weapons
+	if let Some(slot) = tick_input.weapon_slot {
+		let weapon_index = playing_data.weapons.iter().position(|weapon| weapon.slot == slot);
+		if let Some(weapon_index) = weapon_index.filter(|&i| playing_data.unlocked_weapons[i]) {
+			playing_data.weapon_index = weapon_index;
+		}
+	}
+	
+	
+	
+	// fire shots
+	playing_data.fire_cooldown -= dt;
+	let weapon = playing_data.weapons.get(playing_data.weapon_index);
+	let mut did_fire = false;
+	if let (Some(weapon), true) = (weapon, tick_input.fire_held && playing_data.fire_cooldown <= 0.) {
+		did_fire = true;
+		let effects = &playing_data.active_effects;
+		bullet_patterns::fire_player_weapon(weapon, effects, &playing_data.settings, playing_data.player_pos, tick_input.aim_target, &mut playing_data.player_bullets);
+		let fire_wait_coef = if effects.has_rapid_fire() {playing_data.settings.rapid_fire_wait_coef} else {1.};
+		playing_data.fire_cooldown += weapon.fire_wait_secs * fire_wait_coef;
+	}
+	playing_data.fire_cooldown = playing_data.fire_cooldown.max(0.);
+	
+	
+	
 	// update bullet movement
+	update_bullet_positions(&mut playing_data.player_bullets, dt);
+	update_bullet_positions(&mut playing_data.enemy_bullets, dt);
+	
+	
+	
+	// update player health
+	playing_data.invincibility_secs_left = (playing_data.invincibility_secs_left - dt).max(0.);
+	playing_data.time_since_damage += dt;
+	if playing_data.time_since_damage >= playing_data.settings.player_regen_delay_secs {
+		playing_data.player_health = (playing_data.player_health + playing_data.settings.player_regen_per_sec * dt).min(playing_data.player_max_health);
+	}
+	playing_data.active_effects.tick(dt);
+	
+	
+	
+	// update bullet collisions
+	let player_health_before_hits = playing_data.player_health;
+	playing_data.hit_enemies.clear();
+	playing_data.killed_enemies.clear();
 	let mut bullet_datas = BulletDataRefs {
-		bullets: &mut playing_data.player_bullets,
+		player_bullets: &mut playing_data.player_bullets,
+		enemy_bullets: &mut playing_data.enemy_bullets,
 		player_pos: &playing_data.player_pos,
+		prev_player_pos: &playing_data.prev_player_pos,
 		player_health: &mut playing_data.player_health,
+		invincibility_secs_left: &mut playing_data.invincibility_secs_left,
+		time_since_damage: &mut playing_data.time_since_damage,
+		has_shield: playing_data.active_effects.has_shield(),
 		enemies: &mut playing_data.enemies,
+		kills: &mut playing_data.kills,
+		hit_enemies: &mut playing_data.hit_enemies,
+		killed_enemies: &mut playing_data.killed_enemies,
+		spatial_hash: &mut playing_data.spatial_hash,
+		enemy_is_dead: &mut playing_data.enemy_is_dead,
+		settings: &playing_data.settings,
 	};
-	update_bullets(&mut bullet_datas, player_bullet_collision);
-	
-	bullet_datas.bullets = &mut playing_data.enemy_bullets;
-	update_bullets(&mut bullet_datas, enemy_bullet_collision);
+	player_bullet_collision(&mut bullet_datas);
+	enemy_bullet_collision(&mut bullet_datas);
 	
 	
 	
 	// update enemies
-	for enemy in &mut playing_data.enemies {
-		let did_loop = enemy.shoot_timer.tick(dt);
-		if did_loop {
-			let bullet = Bullet::new(enemy.pos, playing_data.player_pos, program_settings::ENEMY_BULLET_SPEED);
-			playing_data.enemy_bullets.push(bullet);
+	let enemies = &mut playing_data.enemies;
+	let archetypes = &playing_data.enemy_archetypes;
+	let firing_target = bullet_patterns::FiringTarget {
+		pos: playing_data.player_pos,
+		vel: playing_data.player_vel,
+	};
+	for i in 0..enemies.len() {
+		let did_loop = enemies.shoot_timer[i].tick(dt);
+		bullet_patterns::update_enemy_firing(
+			&archetypes[enemies.archetype[i]],
+			&mut enemies.firing_state[i],
+			did_loop,
+			enemies.pos[i],
+			&firing_target,
+			dt,
+			&mut playing_data.enemy_bullets,
+		);
+	}
+	for ((vel, pos), archetype) in enemies.vel.iter_mut().zip(&enemies.pos).zip(&enemies.archetype) {
+		let archetype = &archetypes[*archetype];
+		match archetype.movement_style {
+			MovementStyle::Bounce => {}
+			MovementStyle::Chase => *vel = (playing_data.player_pos - *pos).normalize_or_zero() * archetype.speed,
+			MovementStyle::Stationary => *vel = Vec2::ZERO,
 		}
-		enemy.pos += enemy.vel * dt;
-		let radius = program_settings::ENEMY_SIZE / 2.;
-		if enemy.pos.x < radius {
-			enemy.pos.x = radius;
-			enemy.vel.x *= -1.;
+	}
+	for (pos, vel) in enemies.pos.iter_mut().zip(&enemies.vel) {
+		*pos += *vel * dt;
+	}
+	separate_enemies(enemies, archetypes, &mut playing_data.spatial_hash);
+	for ((pos, vel), size) in enemies.pos.iter_mut().zip(&mut enemies.vel).zip(&enemies.size) {
+		let radius = size / 2.;
+		if pos.x < radius {
+			pos.x = radius;
+			vel.x *= -1.;
 		}
-		if enemy.pos.y < radius {
-			enemy.pos.y = radius;
-			enemy.vel.y *= -1.;
+		if pos.y < radius {
+			pos.y = radius;
+			vel.y *= -1.;
 		}
-		if enemy.pos.x > 1. - radius {
-			enemy.pos.x = 1. - radius;
-			enemy.vel.x *= -1.;
+		if pos.x > 1. - radius {
+			pos.x = 1. - radius;
+			vel.x *= -1.;
 		}
-		if enemy.pos.y > 1. - radius {
-			enemy.pos.y = 1. - radius;
-			enemy.vel.y *= -1.;
+		if pos.y > 1. - radius {
+			pos.y = 1. - radius;
+			vel.y *= -1.;
 		}
 	}
 	
 	
 	
+	// update enemy contact
+	enemy_player_contact(playing_data);
+	
+	
+	
+	// update particles
+	let particles = &mut playing_data.particles;
+	let emitters = &playing_data.particle_emitters;
+	let particle_rng = &mut playing_data.particle_rng;
+	particles.update(emitters, dt);
+	for &(pos, bullet_vel) in &playing_data.hit_enemies {
+		particles.emit(emitters, ParticleEffect::EnemyHit, pos, bullet_vel, particle_rng);
+	}
+	for &(_, pos) in &playing_data.killed_enemies {
+		particles.emit(emitters, ParticleEffect::EnemyDeath, pos, Vec2::ZERO, particle_rng);
+	}
+	let player_was_hit = playing_data.player_health < player_health_before_hits;
+	if player_was_hit {
+		particles.emit(emitters, ParticleEffect::PlayerHit, playing_data.player_pos, Vec2::ZERO, particle_rng);
+	}
+	if did_fire {
+		particles.emit(emitters, ParticleEffect::MuzzleFlash, playing_data.player_pos, tick_input.aim_target - playing_data.player_pos, particle_rng);
+	}
+	
+	
+	
+	// update camera
+	let camera = &mut playing_data.camera;
+	let settings = &playing_data.settings;
+	camera.update(settings, playing_data.player_pos, dt);
+	camera.add_trauma(settings.camera_trauma_per_kill * playing_data.killed_enemies.len() as f32);
+	if player_was_hit {
+		camera.add_trauma(settings.camera_trauma_per_player_hit);
+	}
+	
+	
+	
+	// update pickups
+	for &(_, pos) in &playing_data.killed_enemies {
+		if playing_data.rng.gen::<f32>() >= playing_data.settings.pickup_drop_chance {continue;}
+		let kind = PickupKind::ALL[playing_data.rng.gen_range(0..PickupKind::ALL.len())];
+		let pickup = Pickup::new(kind, pos, &playing_data.settings, &mut playing_data.rng);
+		playing_data.pickups.push(pickup);
+	}
+	update_pickups(playing_data, dt);
+	
+	
+	
+	// update scoring
+	for &(archetype, _) in &playing_data.killed_enemies {
+		let score_value = playing_data.enemy_archetypes[archetype].score_value;
+		playing_data.score += (score_value as f32 * playing_data.combo_multiplier) as usize;
+		playing_data.combo_multiplier = (playing_data.combo_multiplier + playing_data.settings.combo_multiplier_step).min(playing_data.settings.combo_multiplier_max);
+		playing_data.time_since_last_kill = 0.;
+	}
+	playing_data.time_since_last_kill += dt;
+	if playing_data.time_since_last_kill > playing_data.settings.combo_decay_delay_secs {
+		playing_data.combo_multiplier = (playing_data.combo_multiplier - playing_data.settings.combo_decay_per_sec * dt).max(1.);
+	}
+	let did_loop = playing_data.survival_score_timer.tick(dt);
+	if did_loop {
+		playing_data.score += playing_data.settings.survival_score_per_interval;
+	}
+	
+	
+	
 	// update enemy spawning
+	if playing_data.level.is_some() {
+		wave_scheduler::update_wave_scheduler(playing_data, dt);
+		return Ok(());
+	}
 	let did_loop = playing_data.enemy_spawn_timer.tick(dt);
 	if did_loop {
 		let timer_duration = &mut playing_data.enemy_spawn_timer.starting_duration;
-		*timer_duration *= program_settings::ENEMY_SPAWN_WAIT_COEF_PERCENT;
+		*timer_duration *= playing_data.enemy_spawn_wait_coef_percent;
 		*timer_duration /= 100;
-		let enemy = Enemy::new();
-		playing_data.enemies.push(enemy);
+		let archetype_names = &playing_data.settings.endless_enemy_archetypes;
+		let archetype_name = archetype_names[playing_data.rng.gen_range(0..archetype_names.len())].clone();
+		spawn_enemy(playing_data, &archetype_name)?;
 	}
 	
 	
@@ -170,6 +390,40 @@ pub fn update_game(playing_data: &mut PlayingData, dt: f32) -> Result<()> {
 
 
 
+pub fn spawn_enemy(playing_data: &mut PlayingData, archetype_name: &str) -> Result<EntityHandle> {
+	let archetype_index =
+		get_enemy_archetype_index(&playing_data.enemy_archetypes, archetype_name)
+		.ok_or_else(|| Error::msg(format!("Could not find enemy archetype \"{archetype_name}\"")))?;
+	Ok(spawn_enemy_from_index(playing_data, archetype_index, &SpawnPosition::Random))
+}
+
+pub fn spawn_enemy_from_index(playing_data: &mut PlayingData, archetype_index: usize, position: &SpawnPosition) -> EntityHandle {
+	let archetype = &playing_data.enemy_archetypes[archetype_index];
+	let mut enemy = Enemy::new(archetype_index, archetype, &mut playing_data.rng);
+	if let Some(pos) = wave_scheduler::get_spawn_pos(position, archetype.size, &mut playing_data.rng) {
+		enemy.pos = pos;
+	}
+	playing_data.enemies.push(enemy)
+}
+
+
+
+
+// switches to the current weapon's upgrade and unlocks it, or gives a score bonus if it's already fully upgraded
+pub fn upgrade_weapon(weapons: &[WeaponDefinition], weapon_index: &mut usize, unlocked_weapons: &mut [bool], score: &mut usize, score_bonus: usize) {
+	let upgrade_index =
+		weapons.get(*weapon_index)
+		.and_then(|weapon| weapon.upgrade.as_deref())
+		.and_then(|upgrade| get_weapon_index(weapons, upgrade));
+	let Some(upgrade_index) = upgrade_index else {
+		*score += score_bonus;
+		return;
+	};
+	unlocked_weapons[upgrade_index] = true;
+	*weapon_index = upgrade_index;
+}
+
+
 
 
 pub fn update_pause_menu(pause_data: &mut PauseData, dt: f32) {
@@ -190,16 +444,62 @@ pub fn update_pause_menu(pause_data: &mut PauseData, dt: f32) {
 
 
 
-pub fn transfer_data_to_gui(app: &mut App, playing_data: &mut PlayingData) -> Result<()> {
+pub fn transfer_data_to_gui(playing_data: &mut PlayingData) -> Result<()> {
 	
 	const GUI_ERROR_MESSAGE: &str = "Could not update gui";
 	let gui = &mut playing_data.gui;
 	
+	// update hud
+	let hud = gui.child_mut_or_message("hud", GUI_ERROR_MESSAGE)?;
+	let score_label = hud.child_mut_or_message("score_label", GUI_ERROR_MESSAGE)?;
+	score_label.text = vec!(format!("Score: {}", playing_data.score));
+	let multiplier_label = hud.child_mut_or_message("multiplier_label", GUI_ERROR_MESSAGE)?;
+	multiplier_label.text = vec!(format!("Combo: x{:.2}", playing_data.combo_multiplier));
+	let health_label = hud.child_mut_or_message("health_label", GUI_ERROR_MESSAGE)?;
+	let health_fraction = (playing_data.player_health / playing_data.player_max_health).clamp(0., 1.);
+	health_label.text = vec!(format!("Health: {:.0}%", health_fraction * 100.));
+	let wave_label = hud.child_mut_or_message("wave_label", GUI_ERROR_MESSAGE)?;
+	wave_label.text = vec!(match &playing_data.level {
+		Some(_) => format!("Wave {}: {}", playing_data.wave_state.wave_number, playing_data.wave_state.wave_name),
+		None => String::from("Endless"),
+	});
+	
+	// update health bar
+	let health_bar = gui.child_mut_or_message("health_bar", GUI_ERROR_MESSAGE)?;
+	let health_bar_fill = health_bar.child_mut_or_message("fill", GUI_ERROR_MESSAGE)?;
+	health_bar_fill.width = health_fraction;
+	
+	// update weapon label
+	let weapon_label = gui.child_mut_or_message("weapon_label", GUI_ERROR_MESSAGE)?;
+	weapon_label.text = vec!(match playing_data.weapons.get(playing_data.weapon_index) {
+		Some(weapon) => format!("Weapon: {} [{}]", weapon.name, weapon.slot),
+		None => String::new(),
+	});
+	
+	// update power-up timers
+	let power_up_label = gui.child_mut_or_message("power_up_label", GUI_ERROR_MESSAGE)?;
+	let effects = &playing_data.active_effects;
+	power_up_label.text = [
+		("Rapid Fire", effects.rapid_fire_secs),
+		("Spread Shot", effects.spread_shot_secs),
+		("Shield", effects.shield_secs),
+	]
+		.into_iter()
+		.filter(|(_, secs)| *secs > 0.)
+		.map(|(name, secs)| format!("{name}: {secs:.1}s"))
+		.collect();
+	if power_up_label.text.is_empty() {
+		power_up_label.text = vec!(String::new());
+	}
+	
 	// update pause menu
 	let pause_data = &playing_data.pause_data;
 	if pause_data.needs_gui_update {
+		let main_pause_menu = gui.child_mut_or_message("main_pause_menu", GUI_ERROR_MESSAGE)?;
+		let seed_label = main_pause_menu.child_mut_or_message("seed_label", GUI_ERROR_MESSAGE)?;
+		seed_label.text = vec!(format!("Seed: {}", playing_data.seed));
 		let curr_menu_alpha = pause_data.curr_menu_transparency;
-		gui::utils::apply_to_all_children(gui.child_mut_or_message("main_pause_menu", GUI_ERROR_MESSAGE)?, |element| {
+		gui::utils::apply_to_all_children(main_pause_menu, |element| {
 			if let Some(default_background_alpha) = element.custom_data.default_background_alpha {
 				element.background_color.a = default_background_alpha * curr_menu_alpha;
 			}
@@ -217,17 +517,12 @@ pub fn transfer_data_to_gui(app: &mut App, playing_data: &mut PlayingData) -> Re
 
 
 
-pub fn process_inputs_after_main(app: &mut App, program_data: &mut ProgramData) {
-	let playing_data = &mut program_data.playing_data;
+pub fn process_inputs_after_main(input: &InputSnapshot, playing_data: &mut PlayingData) {
 	
-	if app.keyboard.was_pressed(KeyCode::Escape) {
+	if input.action_was_pressed(Action::Pause) {
 		let pause_data = &mut playing_data.pause_data;
 		pause_data.is_paused = !pause_data.is_paused;
-	}
-	if app.mouse.left_was_pressed() {
-		let mouse_pos = app.mouse.position().to_vec2() / program_data.last_screen_size.as_vec2();
-		let new_bullet = Bullet::new(playing_data.player_pos, mouse_pos, program_settings::PLAYER_BULLET_SPEED);
-		playing_data.player_bullets.push(new_bullet);
+		playing_data.pending_tick_input.pause_toggled = true;
 	}
 	
 }
@@ -236,77 +531,386 @@ pub fn process_inputs_after_main(app: &mut App, program_data: &mut ProgramData)
 
 
 
+pub fn end_run(program_data: &mut ProgramData) {
+	let playing_data = &program_data.playing_data;
+	save_session_replay(playing_data);
+	let game_over_data = &mut program_data.game_over_data;
+	game_over_data.time_survived = playing_data.get_time_survived();
+	game_over_data.score = playing_data.score;
+	game_over_data.kills = playing_data.kills;
+	game_over_data.level_complete = playing_data.player_health > 0. && playing_data.wave_state.is_finished;
+	program_data.mode = ProgramMode::GameOver;
+	
+	if get_high_score_insert_index(&program_data.high_scores, playing_data.score).is_some() {
+		let entry = HighScoreEntry {
+			name: String::new(),
+			score: playing_data.score,
+			date: get_current_date_string(),
+			seed: playing_data.seed,
+			duration: playing_data.get_time_survived(),
+			difficulty: playing_data.difficulty,
+		};
+		update_for_name_entry::start_name_entry(program_data, entry);
+	}
+}
+
+
+
+pub fn save_session_replay(playing_data: &PlayingData) {
+	let replay = get_session_replay(playing_data);
+	match save_replay(&replay) {
+		Result::Ok (path) => println!("Saved replay to {path:?}"),
+		Result::Err (err) => println!("Warning: could not save replay: {err:?}"),
+	}
+}
+
+
+
+pub fn get_session_replay(playing_data: &PlayingData) -> Replay {
+	Replay {
+		seed: playing_data.seed,
+		level_name: playing_data.level.as_ref().map(|level| level.name.clone()),
+		difficulty: playing_data.difficulty,
+		simulation_hash: get_simulation_hash(&playing_data.settings, &playing_data.loaded_enemy_archetypes, &playing_data.weapons, playing_data.level.as_ref()),
+		custom_difficulty: playing_data.settings.custom_difficulty.clone(),
+		tick_inputs: playing_data.recorded_tick_inputs.clone(),
+	}
+}
+
+
+
+
+
 // holds ALL the data needed to update the bullets
 pub struct BulletDataRefs<'a> {
-	bullets: &'a mut Vec<Bullet>,
+	player_bullets: &'a mut Bullets,
+	enemy_bullets: &'a mut Bullets,
 	player_pos: &'a Vec2,
+	prev_player_pos: &'a Vec2,
 	player_health: &'a mut f32,
-	enemies: &'a mut Vec<Enemy>,
+	invincibility_secs_left: &'a mut f32,
+	time_since_damage: &'a mut f32,
+	has_shield: bool,
+	enemies: &'a mut Enemies,
+	kills: &'a mut usize,
+	// enemy position and bullet velocity of every hit that didn't kill the enemy
+	hit_enemies: &'a mut Vec<(Vec2, Vec2)>,
+	// archetype index and position of every enemy killed this tick
+	killed_enemies: &'a mut Vec<(usize, Vec2)>,
+	spatial_hash: &'a mut SpatialHash,
+	enemy_is_dead: &'a mut Vec<bool>,
+	settings: &'a ProgramSettings,
+}
+
+pub fn update_bullet_positions(bullets: &mut Bullets, dt: f32) {
+	for (pos, vel) in bullets.pos.iter_mut().zip(&bullets.vel) {
+		*pos += *vel * dt;
+	}
+	for i in (0..bullets.len()).rev() {
+		let pos = bullets.pos[i];
+		let is_out_of_bounds =
+			pos.x > 1.1
+			|| pos.x < -0.1
+			|| pos.y > 1.1
+			|| pos.y < -0.1;
+		if is_out_of_bounds {
+			bullets.swap_remove(i);
+		}
+	}
 }
 
-type ShouldRemoveBullet = bool;
 
-pub fn update_bullets(bullet_datas: &mut BulletDataRefs, collision_logic: fn(&mut BulletDataRefs, usize) -> ShouldRemoveBullet) {
-	let mut i = 0;
-	while i < bullet_datas.bullets.len() {
-		let curr_bullet = &mut bullet_datas.bullets[i];
-		
-		// update pos
-		curr_bullet.pos += curr_bullet.vel;
-		if
-			curr_bullet.pos.x > 1.1
-			|| curr_bullet.pos.x < -0.1
-			|| curr_bullet.pos.y > 1.1
-			|| curr_bullet.pos.y < -0.1
-		{
-			bullet_datas.bullets.remove(i);
-			continue; // note: don't inc i
+
+pub fn player_bullet_collision(bullet_datas: &mut BulletDataRefs) {
+	let enemies = &mut *bullet_datas.enemies;
+	let bullets = &mut *bullet_datas.player_bullets;
+	let spatial_hash = &mut *bullet_datas.spatial_hash;
+	spatial_hash.rebuild(enemies.pos.iter().copied());
+	let max_enemy_size = enemies.size.iter().copied().fold(0., f32::max);
+	// sizes are diameters, so a bullet hits when its path comes within both radii of the enemy's center
+	let bullet_radius = bullet_datas.settings.bullet_size / 2.;
+	
+	// indices are only valid until something is removed, so gather handles first and remove afterwards
+	let enemy_is_dead = &mut *bullet_datas.enemy_is_dead;
+	enemy_is_dead.clear();
+	enemy_is_dead.resize(enemies.len(), false);
+	let mut dead_enemies = vec!();
+	let mut hit_bullets = vec!();
+	for bullet_index in 0..bullets.len() {
+		// test the whole path the bullet took this tick so that fast bullets can't skip over enemies
+		let path_start = bullets.prev_pos[bullet_index];
+		let path_end = bullets.pos[bullet_index];
+		let bullet_handle = &bullets.entities.handles[bullet_index];
+		let last_hit_enemy = bullets.last_hit_enemy[bullet_index];
+		let query_radius = max_enemy_size / 2. + bullet_radius + (path_end - path_start).length() / 2.;
+		// take the earliest hit along the path, then the lowest index, so the result doesn't depend on the grid layout
+		let hit_enemy_index =
+			spatial_hash.query((path_start + path_end) / 2., query_radius)
+			.filter(|&i| !enemy_is_dead[i] && Some(enemies.entities.handles[i]) != last_hit_enemy)
+			.filter_map(|i| Some((collision::get_segment_circle_hit_time(path_start, path_end, enemies.pos[i], enemies.size[i] / 2. + bullet_radius)?, i)))
+			.min_by(|(time_a, index_a), (time_b, index_b)| time_a.total_cmp(time_b).then(index_a.cmp(index_b)))
+			.map(|(_, i)| i);
+		let Some(hit_enemy_index) = hit_enemy_index else {continue;};
+		if bullets.pierce_left[bullet_index] > 0 {
+			bullets.pierce_left[bullet_index] -= 1;
+			bullets.last_hit_enemy[bullet_index] = Some(enemies.entities.handles[hit_enemy_index]);
+		} else {
+			hit_bullets.push(*bullet_handle);
 		}
-		
-		// collision logic
-		let should_remove_bullet = collision_logic(bullet_datas, i);
-		if should_remove_bullet {
-			bullet_datas.bullets.remove(i);
-			continue; // note: don't inc i
+		enemies.health[hit_enemy_index] -= bullets.damage[bullet_index];
+		if enemies.health[hit_enemy_index] <= 0. {
+			enemy_is_dead[hit_enemy_index] = true;
+			dead_enemies.push(enemies.entities.handles[hit_enemy_index]);
+			bullet_datas.killed_enemies.push((enemies.archetype[hit_enemy_index], enemies.pos[hit_enemy_index]));
+			*bullet_datas.kills += 1;
+		} else {
+			bullet_datas.hit_enemies.push((enemies.pos[hit_enemy_index], bullets.vel[bullet_index]));
 		}
-		
-		i += 1;
+	}
+	
+	for handle in dead_enemies {
+		enemies.remove(handle);
+	}
+	for handle in hit_bullets {
+		bullets.remove(handle);
 	}
 }
 
 
 
-pub fn player_bullet_collision(bullet_datas: &mut BulletDataRefs, i: usize) -> ShouldRemoveBullet {
-	let curr_bullet = &mut bullet_datas.bullets[i];
+pub fn enemy_bullet_collision(bullet_datas: &mut BulletDataRefs) {
+	// bullets go straight through the player while they're invincible
+	if *bullet_datas.invincibility_secs_left > 0. {return;}
+	let bullets = &mut *bullet_datas.enemy_bullets;
+	let spatial_hash = &mut *bullet_datas.spatial_hash;
+	spatial_hash.rebuild(bullets.pos.iter().copied());
+	let player_pos = *bullet_datas.player_pos;
+	let prev_player_pos = *bullet_datas.prev_player_pos;
+	
+	// both the bullets and the player moved this tick, so test their paths against each other instead of just the end positions
+	let max_bullet_travel = bullets.pos.iter().zip(&bullets.prev_pos).map(|(pos, prev_pos)| (*pos - *prev_pos).length()).fold(0., f32::max);
+	let player_radius = bullet_datas.settings.player_size / 2.;
+	let bullet_radius = bullet_datas.settings.bullet_size / 2.;
+	let query_radius = player_radius + bullet_radius + max_bullet_travel + (player_pos - prev_player_pos).length();
+	
+	// only the strongest bullet counts if several hit on the same tick
+	let mut hit_bullets = vec!();
+	let mut damage = 0f32;
+	for i in spatial_hash.query(player_pos, query_radius) {
+		let hit_time = collision::get_swept_circles_hit_time(bullets.prev_pos[i], bullets.pos[i], bullet_radius, prev_player_pos, player_pos, player_radius);
+		if hit_time.is_some() {
+			hit_bullets.push(bullets.entities.handles[i]);
+			damage = damage.max(bullets.damage[i]);
+		}
+	}
+	if hit_bullets.is_empty() {return;}
 	
-	let hit_enemy =
-		bullet_datas.enemies
-		.iter().enumerate()
-		.find(|(_, enemy)| {
-			let dist = (enemy.pos - curr_bullet.pos).length();
-			dist <= program_settings::ENEMY_SIZE
-		});
+	// the shield still destroys the bullets, it just skips the damage
+	if !bullet_datas.has_shield {
+		*bullet_datas.player_health -= damage;
+		*bullet_datas.invincibility_secs_left = bullet_datas.settings.player_invincibility_secs;
+		*bullet_datas.time_since_damage = 0.;
+	}
+	for handle in hit_bullets {
+		bullets.remove(handle);
+	}
+}
+
+
+
+// pushes overlapping enemies apart, stationary enemies don't get moved and bouncing enemies bounce off each other
+pub fn separate_enemies(enemies: &mut Enemies, archetypes: &[EnemyArchetype], spatial_hash: &mut SpatialHash) {
+	spatial_hash.rebuild(enemies.pos.iter().copied());
+	let max_enemy_size = enemies.size.iter().copied().fold(0., f32::max);
+	
+	// find every pair first so that the result doesn't depend on the order things get pushed in
+	let mut pairs = vec!();
+	for i in 0..enemies.len() {
+		for j in spatial_hash.query(enemies.pos[i], max_enemy_size) {
+			if j <= i {continue;}
+			if collision::get_circle_overlap(enemies.pos[i], enemies.size[i] / 2., enemies.pos[j], enemies.size[j] / 2.) > 0. {
+				pairs.push((i, j));
+			}
+		}
+	}
+	pairs.sort_unstable();
+	
+	for (i, j) in pairs {
+		let overlap = collision::get_circle_overlap(enemies.pos[i], enemies.size[i] / 2., enemies.pos[j], enemies.size[j] / 2.);
+		if overlap <= 0. {continue;}
+		let dir = collision::get_push_dir(enemies.pos[i], enemies.pos[j]);
+		let style_i = archetypes[enemies.archetype[i]].movement_style;
+		let style_j = archetypes[enemies.archetype[j]].movement_style;
+		let (share_i, share_j) = match (style_i == MovementStyle::Stationary, style_j == MovementStyle::Stationary) {
+			(false, false) => (0.5, 0.5),
+			(false, true) => (1., 0.),
+			(true, false) => (0., 1.),
+			(true, true) => continue,
+		};
+		enemies.pos[i] += dir * overlap * share_i;
+		enemies.pos[j] -= dir * overlap * share_j;
+		// only reflect velocities that are still heading into the other enemy
+		let speed_into_j = enemies.vel[i].dot(dir);
+		if style_i == MovementStyle::Bounce && speed_into_j < 0. {
+			enemies.vel[i] -= 2. * speed_into_j * dir;
+		}
+		let speed_into_i = enemies.vel[j].dot(dir);
+		if style_j == MovementStyle::Bounce && speed_into_i > 0. {
+			enemies.vel[j] -= 2. * speed_into_i * dir;
+		}
+	}
+}
+
+
+
+// pushes the player out of any enemies they touch, then damages and knocks them back unless they're invincible
+pub fn enemy_player_contact(playing_data: &mut PlayingData) {
+	let enemies = &playing_data.enemies;
+	let player_radius = playing_data.settings.player_size / 2.;
 	
-	if let Some((hit_enemy_index, _)) = hit_enemy {
-		bullet_datas.enemies.remove(hit_enemy_index);
-		return true
+	let mut push = Vec2::ZERO;
+	let mut damage = 0f32;
+	let mut is_touching = false;
+	for i in 0..enemies.len() {
+		let overlap = collision::get_circle_overlap(playing_data.player_pos, player_radius, enemies.pos[i], enemies.size[i] / 2.);
+		if overlap <= 0. {continue;}
+		is_touching = true;
+		push += collision::get_push_dir(playing_data.player_pos, enemies.pos[i]) * overlap;
+		damage = damage.max(playing_data.enemy_archetypes[enemies.archetype[i]].contact_damage);
+	}
+	if !is_touching {return;}
+	
+	playing_data.player_pos = (playing_data.player_pos + push).clamp(Vec2::splat(player_radius), Vec2::splat(1. - player_radius));
+	if playing_data.invincibility_secs_left > 0. {return;}
+	playing_data.player_vel = push.normalize_or_zero() * playing_data.settings.player_knockback_speed;
+	// same as bullets, the shield only skips the damage
+	if playing_data.active_effects.has_shield() {return;}
+	playing_data.player_health -= damage;
+	playing_data.invincibility_secs_left = playing_data.settings.player_invincibility_secs;
+	playing_data.time_since_damage = 0.;
+}
+
+
+
+// moves the pickups, removes expired ones, and applies the ones the player touches
+pub fn update_pickups(playing_data: &mut PlayingData, dt: f32) {
+	let pickups = &mut playing_data.pickups;
+	let radius = playing_data.settings.pickup_size / 2.;
+	for (pos, vel) in pickups.pos.iter_mut().zip(&mut pickups.vel) {
+		*pos += *vel * dt;
+		if pos.x < radius || pos.x > 1. - radius {vel.x *= -1.;}
+		if pos.y < radius || pos.y > 1. - radius {vel.y *= -1.;}
+		*pos = pos.clamp(Vec2::splat(radius), Vec2::splat(1. - radius));
 	}
 	
-	false
+	let pickup_dist = (playing_data.settings.player_size + playing_data.settings.pickup_size) / 2.;
+	for i in (0..pickups.len()).rev() {
+		pickups.secs_left[i] -= dt;
+		let is_collected = (pickups.pos[i] - playing_data.player_pos).length() <= pickup_dist;
+		if is_collected {
+			let effects = &mut playing_data.active_effects;
+			match pickups.kind[i] {
+				PickupKind::Health => playing_data.player_health = (playing_data.player_health + playing_data.settings.pickup_heal_amount).min(playing_data.player_max_health),
+				PickupKind::RapidFire => effects.rapid_fire_secs = playing_data.settings.power_up_duration_secs,
+				PickupKind::SpreadShot => effects.spread_shot_secs = playing_data.settings.power_up_duration_secs,
+				PickupKind::Shield => effects.shield_secs = playing_data.settings.power_up_duration_secs,
+				PickupKind::ScoreBonus => playing_data.score += playing_data.settings.pickup_score_bonus,
+				PickupKind::WeaponUpgrade => upgrade_weapon(&playing_data.weapons, &mut playing_data.weapon_index, &mut playing_data.unlocked_weapons, &mut playing_data.score, playing_data.settings.pickup_score_bonus),
+			}
+		}
+		if is_collected || pickups.secs_left[i] <= 0. {
+			pickups.swap_remove(i);
+		}
+	}
 }
 
 
 
-pub fn enemy_bullet_collision(bullet_datas: &mut BulletDataRefs, i: usize) -> ShouldRemoveBullet {
-	let curr_bullet = &mut bullet_datas.bullets[i];
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	const ENEMY_SIZE: f32 = 0.1;
+	const PLAYER_SIZE: f32 = 0.1;
+	const BULLET_SIZE: f32 = 0.02;
+	
+	fn new_playing_data() -> PlayingData {
+		let mut playing_data = PlayingData::empty();
+		playing_data.settings.player_size = PLAYER_SIZE;
+		playing_data.settings.bullet_size = BULLET_SIZE;
+		playing_data.player_pos = Vec2::new(0.5, 0.5);
+		playing_data.prev_player_pos = playing_data.player_pos;
+		playing_data.player_health = 10.;
+		playing_data
+	}
 	
-	let dist = (*bullet_datas.player_pos - curr_bullet.pos).length();
-	let is_hit = dist <= program_settings::PLAYER_SIZE;
+	// the bullet's path this tick runs horizontally across the arena, `dist` away from the center
+	fn push_bullet(bullets: &mut Bullets, dist: f32) {
+		bullets.push(Bullet::new(Vec2::new(0.3, 0.5 + dist), Vec2::new(0.7, 0.5 + dist), 1., 1.));
+		bullets.pos[0] = Vec2::new(0.7, 0.5 + dist);
+	}
+	
+	fn run_bullet_collisions(playing_data: &mut PlayingData) {
+		let mut bullet_datas = BulletDataRefs {
+			player_bullets: &mut playing_data.player_bullets,
+			enemy_bullets: &mut playing_data.enemy_bullets,
+			player_pos: &playing_data.player_pos,
+			prev_player_pos: &playing_data.prev_player_pos,
+			player_health: &mut playing_data.player_health,
+			invincibility_secs_left: &mut playing_data.invincibility_secs_left,
+			time_since_damage: &mut playing_data.time_since_damage,
+			has_shield: false,
+			enemies: &mut playing_data.enemies,
+			kills: &mut playing_data.kills,
+			hit_enemies: &mut playing_data.hit_enemies,
+			killed_enemies: &mut playing_data.killed_enemies,
+			spatial_hash: &mut playing_data.spatial_hash,
+			enemy_is_dead: &mut playing_data.enemy_is_dead,
+			settings: &playing_data.settings,
+		};
+		player_bullet_collision(&mut bullet_datas);
+		enemy_bullet_collision(&mut bullet_datas);
+	}
+	
+	fn player_bullet_hits_enemy(dist: f32) -> bool {
+		let mut playing_data = new_playing_data();
+		playing_data.enemies.push(Enemy {
+			archetype: 0,
+			pos: Vec2::new(0.5, 0.5),
+			vel: Vec2::ZERO,
+			size: ENEMY_SIZE,
+			health: 10.,
+			shoot_timer: LoopingTimer::new(Duration::from_secs(1)),
+		});
+		push_bullet(&mut playing_data.player_bullets, dist);
+		run_bullet_collisions(&mut playing_data);
+		playing_data.enemies.health[0] < 10.
+	}
+	
+	fn enemy_bullet_hits_player(dist: f32) -> bool {
+		let mut playing_data = new_playing_data();
+		push_bullet(&mut playing_data.enemy_bullets, dist);
+		run_bullet_collisions(&mut playing_data);
+		playing_data.player_health < 10.
+	}
+	
+	#[test]
+	fn player_bullets_use_enemy_and_bullet_radii() {
+		let hit_dist = ENEMY_SIZE / 2. + BULLET_SIZE / 2.;
+		assert!(player_bullet_hits_enemy(hit_dist - 0.001));
+		assert!(!player_bullet_hits_enemy(hit_dist + 0.001));
+		// passing within the enemy's full size isn't enough
+		assert!(!player_bullet_hits_enemy(ENEMY_SIZE - 0.001));
+	}
 	
-	if is_hit {
-		*bullet_datas.player_health = 0.;
-		return true;
+	#[test]
+	fn enemy_bullets_use_player_and_bullet_radii() {
+		let hit_dist = PLAYER_SIZE / 2. + BULLET_SIZE / 2.;
+		assert!(enemy_bullet_hits_player(hit_dist - 0.001));
+		assert!(!enemy_bullet_hits_player(hit_dist + 0.001));
+		assert!(!enemy_bullet_hits_player(PLAYER_SIZE - 0.001));
 	}
 	
-	false
 }
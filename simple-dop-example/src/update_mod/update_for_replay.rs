@@ -0,0 +1,233 @@
+use crate::prelude::*;
+
+
+
+
+
+// easily keep track of control flow
+pub fn update(input: &InputSnapshot, program_data: &mut ProgramData, dt: f32) -> Result<()> {
+	process_inputs_before_main(input, program_data);
+	process_gui_clicks(input, program_data)?;
+	run_replay_ticks(&mut program_data.replay_data, &mut program_data.playing_data, dt)?;
+	gui::update::update_gui_elements(&mut program_data.replay_data.gui, &input.gui_keyboard_data);
+	transfer_data_to_gui(&mut program_data.replay_data)?;
+	process_inputs_after_main(input, program_data);
+	Ok(())
+}
+
+
+
+
+
+pub fn start_replay(program_data: &mut ProgramData, replay: Replay) -> Result<()> {
+	load_replay(&mut program_data.replay_data, &mut program_data.playing_data, replay)?;
+	program_data.mode = ProgramMode::Replay;
+	Ok(())
+}
+
+
+
+// resets the playing data to how it was when the replay was recorded
+pub fn load_replay(replay_data: &mut ReplayData, playing_data: &mut PlayingData, replay: Replay) -> Result<()> {
+	let replay_level = match &replay.level_name {
+		Some(level_name) => Some(load_level(level_name, &playing_data.loaded_enemy_archetypes).with_context(|| format!("Could not load level \"{level_name}\" for replay"))?),
+		None => None,
+	};
+	if replay.simulation_hash != get_simulation_hash(&playing_data.loaded_settings, &playing_data.loaded_enemy_archetypes, &playing_data.weapons, replay_level.as_ref()) {
+		return Err(Error::msg("Replay was recorded with different settings, enemies, weapons, or level than the ones in assets, so it wouldn't play back the same"));
+	}
+	let seed_override = playing_data.seed_override.replace(replay.seed);
+	let selected_level = std::mem::replace(&mut playing_data.selected_level, replay_level);
+	let difficulty = std::mem::replace(&mut playing_data.difficulty, replay.difficulty);
+	let custom_difficulty = std::mem::replace(&mut playing_data.loaded_settings.custom_difficulty, replay.custom_difficulty.clone());
+	playing_data.reset();
+	playing_data.seed_override = seed_override;
+	playing_data.selected_level = selected_level;
+	playing_data.difficulty = difficulty;
+	playing_data.loaded_settings.custom_difficulty = custom_difficulty;
+	
+	replay_data.replay = replay;
+	replay_data.next_tick_index = 0;
+	replay_data.last_fired_aim_target = None;
+	replay_data.is_paused = false;
+	replay_data.is_fast_forwarding = false;
+	replay_data.step_requested = false;
+	
+	Ok(())
+}
+
+
+
+
+
+pub fn process_inputs_before_main(input: &InputSnapshot, program_data: &mut ProgramData) {
+	let replay_data = &mut program_data.replay_data;
+	
+	if input.action_was_pressed(Action::ReplayPause) {
+		replay_data.is_paused = !replay_data.is_paused;
+	}
+	if input.action_was_pressed(Action::ReplayFastForward) {
+		replay_data.is_fast_forwarding = !replay_data.is_fast_forwarding;
+	}
+	if input.action_was_pressed(Action::ReplayStep) {
+		replay_data.step_requested = true;
+	}
+	
+}
+
+
+
+
+
+pub fn process_gui_clicks(input: &InputSnapshot, program_data: &mut ProgramData) -> Result<()> {
+	
+	let mouse_pos = input.mouse_pos.as_ivec2();
+	if input.left_mouse_was_pressed {
+		let hovered_elements = gui::utils::get_hovered_elements(&program_data.replay_data.gui, (mouse_pos.x, mouse_pos.y), input.screen_size.to_tuple());
+		for element in hovered_elements {
+			if let Some(click_fn) = element.custom_data.click_fn {
+				let result = click_fn(program_data);
+				if let Err(err) = result {
+					panic!("Error while processing button press: {err}");
+				}
+				break;
+			}
+		}
+	}
+	
+	Ok(())
+}
+
+
+
+pub fn set_click_fns(gui: &mut GuiElement<CustomGuiData>) -> Result<()> {
+	
+	fn set_click_fn(element: &mut GuiElement<CustomGuiData>, click_fn: fn(&mut ProgramData) -> Result<()>) {
+		element.custom_data.click_fn = Some(click_fn);
+	}
+	const GUI_ERROR_MESSAGE: &str = "Could not add click function";
+	
+	let replay_controls = gui.child_mut_or_message("replay_controls", GUI_ERROR_MESSAGE)?; {
+		
+		fn pause_button(program_data: &mut ProgramData) -> Result<()> {
+			let replay_data = &mut program_data.replay_data;
+			replay_data.is_paused = !replay_data.is_paused;
+			Ok(())
+		}
+		set_click_fn(replay_controls.child_mut_or_message("pause_button", GUI_ERROR_MESSAGE)?, pause_button);
+		
+		fn fast_forward_button(program_data: &mut ProgramData) -> Result<()> {
+			let replay_data = &mut program_data.replay_data;
+			replay_data.is_fast_forwarding = !replay_data.is_fast_forwarding;
+			Ok(())
+		}
+		set_click_fn(replay_controls.child_mut_or_message("fast_forward_button", GUI_ERROR_MESSAGE)?, fast_forward_button);
+		
+		fn step_button(program_data: &mut ProgramData) -> Result<()> {
+			program_data.replay_data.step_requested = true;
+			Ok(())
+		}
+		set_click_fn(replay_controls.child_mut_or_message("step_button", GUI_ERROR_MESSAGE)?, step_button);
+		
+		fn exit_button(program_data: &mut ProgramData) -> Result<()> {
+			program_data.mode = ProgramMode::MainMenu;
+			program_data.main_menu_data.reset();
+			Ok(())
+		}
+		set_click_fn(replay_controls.child_mut_or_message("exit_button", GUI_ERROR_MESSAGE)?, exit_button);
+		
+	}
+	
+	Ok(())
+}
+
+
+
+
+
+// plays back the recorded tick inputs through the exact same `update_game()` that recorded them
+pub fn run_replay_ticks(replay_data: &mut ReplayData, playing_data: &mut PlayingData, dt: f32) -> Result<()> {
+	let tick_inputs = &replay_data.replay.tick_inputs;
+	
+	let mut ticks_to_run = 0;
+	if replay_data.is_paused {
+		if replay_data.step_requested {
+			ticks_to_run = 1;
+			playing_data.tick_accumulator = 0.;
+		}
+	} else {
+		let speed = if replay_data.is_fast_forwarding {program_settings::REPLAY_FAST_FORWARD_SPEED} else {1.};
+		playing_data.tick_accumulator += dt * speed;
+		while playing_data.tick_accumulator >= program_settings::TICK_DURATION_SECS {
+			playing_data.tick_accumulator -= program_settings::TICK_DURATION_SECS;
+			ticks_to_run += 1;
+			// same as `run_game_ticks()`, drop the backlog after a long stall instead of trying to catch up all at once
+			if ticks_to_run >= program_settings::MAX_TICKS_PER_FRAME {
+				playing_data.tick_accumulator = playing_data.tick_accumulator.min(program_settings::TICK_DURATION_SECS);
+				break;
+			}
+		}
+	}
+	replay_data.step_requested = false;
+	
+	for _ in 0..ticks_to_run {
+		let Some(tick_input) = tick_inputs.get(replay_data.next_tick_index) else {
+			replay_data.is_paused = true;
+			playing_data.tick_accumulator = 0.;
+			break;
+		};
+		update_for_playing::update_game(playing_data, tick_input, program_settings::TICK_DURATION_SECS)?;
+		replay_data.next_tick_index += 1;
+		if tick_input.fire_held {
+			replay_data.last_fired_aim_target = Some(tick_input.aim_target);
+		}
+		// stop where the player paused so testers can see what was happening
+		if tick_input.pause_toggled {
+			replay_data.is_paused = true;
+			playing_data.tick_accumulator = 0.;
+			break;
+		}
+	}
+	
+	Ok(())
+}
+
+
+
+
+
+pub fn transfer_data_to_gui(replay_data: &mut ReplayData) -> Result<()> {
+	const GUI_ERROR_MESSAGE: &str = "Could not update gui";
+	let gui = &mut replay_data.gui;
+	
+	let tick_count = replay_data.replay.tick_inputs.len();
+	let progress_label = gui.child_mut_or_message("progress_label", GUI_ERROR_MESSAGE)?;
+	progress_label.text = vec!(format!(
+		"Tick {} / {}   (seed: {}){}",
+		replay_data.next_tick_index,
+		tick_count,
+		replay_data.replay.seed,
+		if replay_data.next_tick_index >= tick_count {"   - finished"} else {""},
+	));
+	
+	let replay_controls = gui.child_mut_or_message("replay_controls", GUI_ERROR_MESSAGE)?;
+	let pause_button = replay_controls.child_mut_or_message("pause_button", GUI_ERROR_MESSAGE)?;
+	pause_button.text = vec!(String::from(if replay_data.is_paused {"Play"} else {"Pause"}));
+	let fast_forward_button = replay_controls.child_mut_or_message("fast_forward_button", GUI_ERROR_MESSAGE)?;
+	fast_forward_button.has_border = replay_data.is_fast_forwarding;
+	
+	Ok(())
+}
+
+
+
+
+
+pub fn process_inputs_after_main(input: &InputSnapshot, program_data: &mut ProgramData) {
+	
+	if input.action_was_pressed(Action::Back) {
+		program_data.mode = ProgramMode::MainMenu;
+		program_data.main_menu_data.reset();
+	}
+	
+}
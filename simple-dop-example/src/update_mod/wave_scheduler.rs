@@ -0,0 +1,85 @@
+use crate::prelude::*;
+use notan::random::rand::Rng;
+
+
+
+// runs the current level's events, only called when `PlayingData::level` is set
+pub fn update_wave_scheduler(playing_data: &mut PlayingData, dt: f32) {
+	// take the level out so that enemies can be spawned while reading it
+	let Some(level) = playing_data.level.take() else {return;};
+	
+	// only one timed event gets `dt` per tick, instant events all happen right away
+	let mut dt_was_used = false;
+	while let Some(event) = level.events.get(playing_data.wave_state.next_event_index) {
+		let wave_state = &mut playing_data.wave_state;
+		
+		if !wave_state.event_started {
+			wave_state.event_started = true;
+			match event {
+				WaveEvent::Wait {secs} => wave_state.event_timer = *secs,
+				WaveEvent::Spawn {count, ..} => {
+					wave_state.spawns_left = *count;
+					wave_state.event_timer = 0.;
+				}
+				_ => {}
+			}
+		}
+		
+		let is_done = match event {
+			
+			WaveEvent::StartWave {name} => {
+				wave_state.wave_number += 1;
+				wave_state.wave_name = name.clone();
+				true
+			}
+			
+			WaveEvent::Wait {..} => {
+				if !dt_was_used {
+					wave_state.event_timer -= dt;
+					dt_was_used = true;
+				}
+				wave_state.event_timer <= 0.
+			}
+			
+			WaveEvent::WaitUntilCleared => playing_data.enemies.is_empty(),
+			
+			WaveEvent::Spawn {archetype, position, interval_secs, ..} => {
+				if !dt_was_used && wave_state.event_timer > 0. {
+					wave_state.event_timer -= dt;
+					dt_was_used = true;
+				}
+				while playing_data.wave_state.spawns_left > 0 && playing_data.wave_state.event_timer <= 0. {
+					update_for_playing::spawn_enemy_from_index(playing_data, *archetype, position);
+					playing_data.wave_state.spawns_left -= 1;
+					playing_data.wave_state.event_timer += interval_secs;
+				}
+				playing_data.wave_state.spawns_left == 0
+			}
+			
+		};
+		if !is_done {break;}
+		
+		let wave_state = &mut playing_data.wave_state;
+		wave_state.next_event_index += 1;
+		wave_state.event_started = false;
+	}
+	
+	let wave_state = &mut playing_data.wave_state;
+	wave_state.is_finished = wave_state.next_event_index >= level.events.len() && playing_data.enemies.is_empty();
+	playing_data.level = Some(level);
+}
+
+
+
+// returns None when the enemy should keep the random position it was created with
+pub fn get_spawn_pos(position: &SpawnPosition, size: f32, rng: &mut impl Rng) -> Option<Vec2> {
+	let edge = size / 2.;
+	match *position {
+		SpawnPosition::Random => None,
+		SpawnPosition::Top    => Some(Vec2::new(rng.gen(), edge)),
+		SpawnPosition::Bottom => Some(Vec2::new(rng.gen(), 1. - edge)),
+		SpawnPosition::Left   => Some(Vec2::new(edge, rng.gen())),
+		SpawnPosition::Right  => Some(Vec2::new(1. - edge, rng.gen())),
+		SpawnPosition::At (pos) => Some(pos),
+	}
+}
@@ -26,6 +26,61 @@ pub fn get_program_file_path(input: impl AsRef<Path>) -> PathBuf {
 
 
 
+// formats today's (utc) date as yyyy-mm-dd
+pub fn get_current_date_string() -> String {
+	let secs = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
+	let days = (secs / 86400) as i64;
+	// civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+	let z = days + 719468;
+	let era = z.div_euclid(146097);
+	let day_of_era = z.rem_euclid(146097);
+	let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let month_index = (5 * day_of_year + 2) / 153;
+	let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+	let month = if month_index < 10 {month_index + 3} else {month_index - 9};
+	let year = year_of_era + era * 400 + (month <= 2) as i64;
+	format!("{year:04}-{month:02}-{day:02}")
+}
+
+
+
+pub struct CommandLineArgs {
+	pub seed: Option<u64>,
+	pub replay_path: Option<PathBuf>,
+	pub level_name: Option<String>,
+}
+
+pub fn parse_command_line_args() -> Result<CommandLineArgs> {
+	let mut output = CommandLineArgs {
+		seed: None,
+		replay_path: None,
+		level_name: None,
+	};
+	let mut args = std::env::args().skip(1);
+	while let Some(arg) = args.next() {
+		match &*arg {
+			"--seed" => {
+				let value = args.next().ok_or_else(|| Error::msg("Expected a value after \"--seed\""))?;
+				let seed = value.parse::<u64>().with_context(|| format!("Invalid seed: \"{value}\""))?;
+				output.seed = Some(seed);
+			}
+			"--replay" => {
+				let value = args.next().ok_or_else(|| Error::msg("Expected a file path after \"--replay\""))?;
+				output.replay_path = Some(PathBuf::from(value));
+			}
+			"--level" => {
+				let value = args.next().ok_or_else(|| Error::msg("Expected a level name after \"--level\""))?;
+				output.level_name = Some(value);
+			}
+			_ => return Err(Error::msg(format!("Unknown command line argument: \"{arg}\""))),
+		}
+	}
+	Ok(output)
+}
+
+
+
 pub fn load_texture(path: impl AsRef<Path>, gfx: &mut Graphics) -> Result<Texture> {
 	let texture_bytes = fs::read(path)?;
 	gfx
@@ -38,3 +93,63 @@ pub fn load_texture(path: impl AsRef<Path>, gfx: &mut Graphics) -> Result<Textur
 
 
 pub struct PackagedErrors (Vec<Error>);
+
+impl PackagedErrors {
+	pub fn new(errors: Vec<Error>) -> Self {
+		Self (errors)
+	}
+}
+
+impl std::fmt::Display for PackagedErrors {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "{} error(s) occurred:", self.0.len())?;
+		for err in &self.0 {
+			writeln!(f, "{err:#}")?;
+		}
+		std::fmt::Result::Ok(())
+	}
+}
+
+impl std::fmt::Debug for PackagedErrors {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		std::fmt::Display::fmt(self, f)
+	}
+}
+
+impl StdError for PackagedErrors {}
+
+
+
+// 64-bit FNV-1a, std's `DefaultHasher` isn't guaranteed to give the same output across rust versions, and these hashes are saved to replay files
+pub struct StableHasher {
+	hash: u64,
+}
+
+impl StableHasher {
+	pub fn new() -> Self {
+		Self {
+			hash: 0xcbf29ce484222325,
+		}
+	}
+	pub fn write_bytes(&mut self, bytes: &[u8]) {
+		for byte in bytes {
+			self.hash ^= *byte as u64;
+			self.hash = self.hash.wrapping_mul(0x100000001b3);
+		}
+	}
+	pub fn write_u64(&mut self, value: u64) {
+		self.write_bytes(&value.to_le_bytes());
+	}
+	// hashes the exact bits, so 0. and -0. are different
+	pub fn write_f32(&mut self, value: f32) {
+		self.write_bytes(&value.to_bits().to_le_bytes());
+	}
+	// the length goes first so that ("ab", "c") and ("a", "bc") don't hash the same
+	pub fn write_str(&mut self, value: &str) {
+		self.write_u64(value.len() as u64);
+		self.write_bytes(value.as_bytes());
+	}
+	pub fn finish(&self) -> u64 {
+		self.hash
+	}
+}